@@ -0,0 +1,162 @@
+//! # dfa! macro
+//! Builds the automaton for a fixed regex while compiling, so a program can match it without this project's
+//! command line program and without building anything at run time.
+//!
+//! ## Operation:
+//! ```text
+//!     - let matches = dfa_macro::dfa!("a*b|\\d+");
+//!         - matches is a fn(&str) -> bool accepting exactly the strings `cargo run "a*b|\d+"` accepts with --construction thompson
+//!         - The expansion holds the transition table as statics, nothing is parsed or built when the program runs
+//!         - An invalid regex, or one needing more than 10000 states, is a compile error pointing at the string, instead of an error and an exit
+//! ```
+//!
+//! The regex is built by the command line program's library (awoods_project1::compile), so the two always agree.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+use awoods_project1::dfa::Dfa;
+use awoods_project1::{compile, STATE_LIMIT};
+
+/// For compiling a regex into a matcher function while compiling the program using it
+/// - Input: One string literal holding the regex, written the same way as on the command line
+/// - Output: An expression of type fn(&str) -> bool, or a compile error at the literal if the regex is invalid
+#[proc_macro]
+pub fn dfa(input: TokenStream) -> TokenStream {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    let literal = match tokens.as_slice() {
+        [TokenTree::Literal(literal)] => literal.clone(),
+        [TokenTree::Group(group)] if group.delimiter() == Delimiter::None => {
+            //a literal passed through a macro_rules! macro arrives wrapped in an invisible group
+            return dfa(group.stream());
+        }
+        _ => {
+            let span = tokens.first().map_or_else(Span::call_site, |t| t.span());
+            return compile_error("dfa! takes one string literal holding a regex", span);
+        }
+    };
+    let result = unquote(&literal.to_string()).and_then(|pattern| {
+        let dfa = build(&pattern)?;
+        Ok(matcher(&dfa))
+    });
+    match result {
+        Ok(source) => source.parse().expect("generated matcher is valid Rust"),
+        Err(message) => compile_error(&message, literal.span()),
+    }
+}
+
+/// For building the automaton of a regex the same way the command line program does for --construction thompson
+/// - Input: The regex
+/// - Output: The Dfa, or a message saying why the regex is invalid or needs too many states
+fn build(pattern: &str) -> Result<Dfa, String> {
+    compile(pattern, Some("thompson"), STATE_LIMIT)
+}
+
+/// For reading the text of a string literal token
+/// - Input: The literal as written in the source, quotes and escapes included
+/// - Output: The string it stands for, or a message if it is not a string literal
+fn unquote(literal: &str) -> Result<String, String> {
+    if let Some(raw) = literal.strip_prefix('r') {
+        //raw string, r"..." or r#"..."# with any number of #
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = &raw[hashes..raw.len() - hashes];
+        return match body.strip_prefix('"').and_then(|b| b.strip_suffix('"')) {
+            Some(text) => Ok(text.to_string()),
+            None => Err("dfa! takes a string literal".to_string()),
+        };
+    }
+    let body = match literal.strip_prefix('"').and_then(|b| b.strip_suffix('"')) {
+        Some(body) => body,
+        None => return Err("dfa! takes a string literal".to_string()),
+    };
+    let mut text = String::new();
+    let mut chars = body.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            text.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => text.push('\\'),
+            Some('"') => text.push('"'),
+            Some('\'') => text.push('\''),
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('r') => text.push('\r'),
+            Some('0') => text.push('\0'),
+            _ => return Err("dfa! does not support this escape, write \\\\ for a backslash".to_string()),
+        }
+    }
+    Ok(text)
+}
+
+/// For writing the expansion: the transition table as statics, and a function walking it like check_string
+/// usize::MAX marks a missing transition, and a character outside the alphabet rejects
+/// - Input: The Dfa
+/// - Output: String holding a block expression evaluating to the matcher function
+fn matcher(dfa: &Dfa) -> String {
+    let alphabet: Vec<String> = dfa.alphabet.iter().map(|c| format!("{:?}", c)).collect();
+    let rows: Vec<String> = dfa.transitions.iter().map(|row| {
+        let cells: Vec<String> = row.iter().map(|t| t.map_or("NONE".to_string(), |to| to.to_string())).collect();
+        format!("[{}]", cells.join(", "))
+    }).collect();
+    let accepting: Vec<String> = (0..dfa.transitions.len()).map(|st| dfa.is_accepting(st).to_string()).collect();
+
+    let mut out = String::from("{\n");
+    out.push_str("    const NONE: usize = usize::MAX;\n");
+    out.push_str(&format!("    static ALPHABET: [char; {}] = [{}];\n", alphabet.len(), alphabet.join(", ")));
+    out.push_str(&format!("    static TRANSITIONS: [[usize; {}]; {}] = [{}];\n", alphabet.len(), rows.len(), rows.join(", ")));
+    out.push_str(&format!("    static ACCEPTING: [bool; {}] = [{}];\n", accepting.len(), accepting.join(", ")));
+    out.push_str("    fn matches(input: &str) -> bool {\n");
+    out.push_str(&format!("        let mut state: usize = {};\n", dfa.start));
+    out.push_str("        for c in input.chars() {\n");
+    out.push_str("            let symbol = match ALPHABET.iter().position(|&x| x == c) {\n");
+    out.push_str("                Some(symbol) => symbol,\n");
+    out.push_str("                None => return false,\n");
+    out.push_str("            };\n");
+    out.push_str("            state = TRANSITIONS[state][symbol];\n");
+    out.push_str("            if state == NONE {\n                return false;\n            }\n");
+    out.push_str("        }\n");
+    out.push_str("        ACCEPTING[state]\n");
+    out.push_str("    }\n");
+    out.push_str("    matches as fn(&str) -> bool\n");
+    out.push_str("}\n");
+    out
+}
+
+/// For reporting a problem as a compile error
+/// - Input: The message, and where in the source it should point
+/// - Output: Tokens for compile_error!("message") placed at the span
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let mut literal = Literal::string(message);
+    literal.set_span(span);
+    let mut bang = Punct::new('!', Spacing::Alone);
+    bang.set_span(span);
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(literal).into());
+    group.set_span(span);
+    vec![
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(bang),
+        TokenTree::Group(group),
+    ].into_iter().collect()
+}
+
+#[test]
+fn test_build(){
+    let dfa = build("a*b|\\d+").unwrap();
+    assert!(dfa.accepts("b"));
+    assert!(dfa.accepts("aab"));
+    assert!(dfa.accepts("42"));
+    assert!(!dfa.accepts("a"));
+    assert!(!dfa.accepts("4b"));
+    assert!(build("a\\x").unwrap_err().contains("position 1"));
+    assert!(build("A").unwrap_err().contains("not in the alphabet"));
+    assert!(build("(ab").is_err());
+
+    assert_eq!(unquote("\"a*b|\\\\d+\""), Ok("a*b|\\d+".to_string()));
+    assert_eq!(unquote("r#\"\\w+\"#"), Ok("\\w+".to_string()));
+    assert!(unquote("1").is_err());
+
+    let source = matcher(&build("ab").unwrap());
+    assert!(source.contains("static ACCEPTING: [bool; "));
+    assert!(source.ends_with("    matches as fn(&str) -> bool\n}\n"));
+}
@@ -0,0 +1,30 @@
+//! # Expanding dfa!
+//! Uses the macro the way another program would, so the generated statics and matcher have to compile and run.
+
+//a literal passed through a macro_rules! macro, like in a program wrapping dfa! in its own macro
+macro_rules! wrapped {
+    ($pattern:literal) => {
+        dfa_macro::dfa!($pattern)
+    };
+}
+
+#[test]
+fn test_expand(){
+    let matches = dfa_macro::dfa!("a*b|\\d+");
+    assert!(matches("b"));
+    assert!(matches("aab"));
+    assert!(matches("42"));
+    assert!(!matches(""));
+    assert!(!matches("a"));
+    assert!(!matches("4b"));
+    assert!(!matches("B")); //outside the alphabet
+
+    let identifier = dfa_macro::dfa!(r"\w(\w|\d)*");
+    assert!(identifier("x1"));
+    assert!(!identifier("1x"));
+
+    //& and ~ are built from the syntax tree too
+    let not_keyword = wrapped!("\\w+~(if|else)");
+    assert!(not_keyword("iff"));
+    assert!(!not_keyword("if"));
+}
@@ -0,0 +1,233 @@
+//! # Binary export and import of a Dfa
+//! JSON is easy to read but slow to load for big automata, this format is small and quick to read back.
+//! Layout (version 1), all numbers little endian:
+//! ```text
+//! "AWDF"                                  //4 byte magic
+//! version            u8                   //format version, 1
+//! pattern            u32 length + UTF-8   //regex the automaton came from, empty if unknown
+//! alphabet           u32 count + u32 each //symbols as char codes, all must be in SIGMA
+//! states             u32                  //number of states, numbered 0..n
+//! start              u32                  //the start state
+//! width              u8                   //bytes per transition: 1, 2, or 4
+//! transitions        states * alphabet    //row by row, the largest value of the width means no transition
+//! accepting          (states + 7) / 8     //bitset, state i is bit i % 8 of byte i / 8
+//! checksum           u64                  //FNV-1a of everything before it
+//! ```
+//! The same hash names the files in the --cache-dir cache, so a regex built once is loaded instead of built again.
+
+use crate::dfa::Dfa;
+use crate::SIGMA;
+
+const MAGIC: &[u8; 4] = b"AWDF";
+const VERSION: u8 = 1;
+
+/// For writing a Dfa in the binary format above
+/// - Input: The Dfa
+/// - Output: The bytes of the file
+pub fn dfa_to_bytes(dfa: &Dfa) -> Vec<u8> {
+    let states = dfa.transitions.len();
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(VERSION);
+    out.extend_from_slice(&(dfa.pattern.len() as u32).to_le_bytes());
+    out.extend_from_slice(dfa.pattern.as_bytes());
+    out.extend_from_slice(&(dfa.alphabet.len() as u32).to_le_bytes());
+    for sym in &dfa.alphabet {
+        out.extend_from_slice(&(*sym as u32).to_le_bytes());
+    }
+    out.extend_from_slice(&(states as u32).to_le_bytes());
+    out.extend_from_slice(&(dfa.start as u32).to_le_bytes());
+
+    //the narrowest width whose largest value is free to mean no transition
+    let width: usize = if states < 0xff {1} else if states < 0xffff {2} else {4};
+    out.push(width as u8);
+    for row in &dfa.transitions {
+        for target in row {
+            let value = target.map_or(u32::MAX, |to| to as u32);
+            out.extend_from_slice(&value.to_le_bytes()[..width]);
+        }
+    }
+
+    let mut accepting = vec![0u8; states.div_ceil(8)];
+    for st in &dfa.accepting {
+        accepting[st / 8] |= 1 << (st % 8);
+    }
+    out.extend_from_slice(&accepting);
+
+    let checksum = fnv1a(&out);
+    out.extend_from_slice(&checksum.to_le_bytes());
+    out
+}
+
+/// For reading a Dfa back from the binary format above
+/// - Input: The bytes of the file
+/// - Output: The Dfa, or a message saying what is wrong with the file
+pub fn dfa_from_bytes(bytes: &[u8]) -> Result<Dfa, String> {
+    if bytes.len() < MAGIC.len() + 1 || &bytes[..MAGIC.len()] != MAGIC {
+        return Err("not an awoods-dfa binary file".to_string());
+    }
+    if bytes[MAGIC.len()] != VERSION {
+        return Err(format!("unsupported version {}", bytes[MAGIC.len()]));
+    }
+    //check the whole file before trusting any of the lengths in it
+    if bytes.len() < MAGIC.len() + 1 + 8 {
+        return Err("file ends early".to_string());
+    }
+    let (body, checksum) = bytes.split_at(bytes.len() - 8);
+    if fnv1a(body).to_le_bytes() != checksum {
+        return Err("checksum does not match, the file is damaged".to_string());
+    }
+
+    let mut reader = Reader { bytes: body, pos: MAGIC.len() + 1 };
+    let pattern_len = reader.u32()? as usize;
+    let pattern = match String::from_utf8(reader.take(pattern_len)?.to_vec()) {
+        Ok(pattern) => pattern,
+        Err(_) => return Err("pattern is not valid UTF-8".to_string()),
+    };
+
+    let mut alphabet = Vec::new();
+    for _ in 0..reader.u32()? {
+        let sym = match std::char::from_u32(reader.u32()?) {
+            Some(sym) if SIGMA.contains(&sym) => sym,
+            _ => return Err("alphabet has a symbol that is not in SIGMA".to_string()),
+        };
+        if alphabet.contains(&sym) {
+            return Err(format!("symbol '{}' is listed twice", sym));
+        }
+        alphabet.push(sym);
+    }
+
+    let states = reader.u32()? as usize;
+    let start = reader.u32()? as usize;
+    if start >= states {
+        return Err(format!("start state {} does not exist", start));
+    }
+    let width = reader.take(1)?[0] as usize;
+    if width != 1 && width != 2 && width != 4 {
+        return Err(format!("transition width {} is not 1, 2, or 4", width));
+    }
+    //a file can have a good checksum and still claim billions of states, so make sure they fit before allocating
+    let needed = states.checked_mul(alphabet.len())
+        .and_then(|cells| cells.checked_mul(width))
+        .and_then(|table| table.checked_add(states.div_ceil(8)));
+    match needed {
+        Some(needed) if needed <= body.len() - reader.pos => {}
+        _ => return Err(format!("{} states do not fit in the file", states)),
+    }
+    let none = (u32::MAX >> (32 - 8 * width)) as usize;
+    let mut transitions = Vec::with_capacity(states);
+    for _ in 0..states {
+        let mut row = Vec::with_capacity(alphabet.len());
+        for _ in 0..alphabet.len() {
+            let mut value = [0u8; 4];
+            value[..width].copy_from_slice(reader.take(width)?);
+            let to = u32::from_le_bytes(value) as usize;
+            if to == none {
+                row.push(None);
+            }
+            else if to < states {
+                row.push(Some(to));
+            }
+            else {
+                return Err(format!("transition to state {} which does not exist", to));
+            }
+        }
+        transitions.push(row);
+    }
+
+    let bits = reader.take(states.div_ceil(8))?;
+    let accepting = (0..states).filter(|st| bits[st / 8] & (1 << (st % 8)) != 0).collect();
+    if reader.pos != body.len() {
+        return Err("extra bytes after the accepting states".to_string());
+    }
+    Ok(Dfa { pattern, alphabet, transitions, start, accepting })
+}
+
+/// For naming the file a regex's automaton is cached in
+/// Everything that changes the automaton built goes into the hash: the construction, the alphabet, and the regex
+/// - Input: The regex, and the --construction it is built with (None for the original parser)
+/// - Output: File name, the hash in hex followed by .dfa
+pub fn cache_name(pattern: &str, construction: Option<&str>) -> String {
+    let alphabet: String = SIGMA.iter().collect();
+    let key = format!("{}\0{}\0{}\0{}", VERSION, construction.unwrap_or("parse_regex"), alphabet, pattern);
+    format!("{:016x}.dfa", fnv1a(key.as_bytes()))
+}
+
+/// For hashing bytes with 64 bit FNV-1a, used for the checksum and the cache file names
+/// - Input: The bytes
+/// - Output: The hash
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+/// Reads the file from front to back
+/// - bytes: The file, without the checksum
+/// - pos: Index of the next byte to read
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// For reading the next n bytes
+    /// - Input: How many bytes
+    /// - Output: The bytes, or a message if the file ends first
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() - self.pos < n {
+            return Err("file ends early".to_string());
+        }
+        self.pos += n;
+        Ok(&self.bytes[self.pos - n..self.pos])
+    }
+
+    /// For reading the next little endian u32
+    /// - Input: None
+    /// - Output: The number, or a message if the file ends first
+    fn u32(&mut self) -> Result<u32, String> {
+        let mut value = [0u8; 4];
+        value.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(value))
+    }
+}
+
+#[test]
+fn test_binary_round_trip(){
+    let dfa = Dfa {
+        pattern: "a*\\d".to_string(),
+        alphabet: vec!['a', '1'],
+        transitions: vec![vec![Some(0), Some(1)], vec![None, None]],
+        start: 0,
+        accepting: vec![1],
+    };
+    let bytes = dfa_to_bytes(&dfa);
+    assert_eq!(dfa_from_bytes(&bytes), Ok(dfa.clone()));
+
+    //enough states to need 2 byte transitions
+    let mut big = dfa;
+    big.transitions = (0..300).map(|st| vec![Some((st + 1) % 300), None]).collect();
+    big.accepting = vec![0, 255, 299];
+    assert_eq!(dfa_from_bytes(&dfa_to_bytes(&big)), Ok(big));
+
+    //any damage is caught by the checksum
+    let mut damaged = bytes.clone();
+    damaged[12] ^= 1;
+    assert!(dfa_from_bytes(&damaged).unwrap_err().contains("checksum"));
+    assert!(dfa_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(dfa_from_bytes(b"{\"format\": \"awoods-dfa\"}").is_err());
+
+    //a forged state count with a checksum to match is refused before anything is allocated for it
+    let mut forged = bytes[..bytes.len() - 8].to_vec();
+    let states_at = 4 + 1 + 4 + 4 + 4 + 4 * 2; //after the magic, version, "a*\d", and the two symbols
+    forged[states_at..states_at + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    let checksum = fnv1a(&forged);
+    forged.extend_from_slice(&checksum.to_le_bytes());
+    assert!(dfa_from_bytes(&forged).unwrap_err().contains("do not fit"));
+
+    assert_ne!(cache_name("a*b", None), cache_name("a*b", Some("thompson")));
+    assert!(cache_name("a*b", None).ends_with(".dfa"));
+}
@@ -0,0 +1,270 @@
+//! # Code generation
+//! Writes the automaton out as a function in Rust, C, or JavaScript, so a pattern can be checked without this program.
+//! Every language is written from the same list of moves, so the generated functions accept exactly the strings
+//! check_string does: the whole input has to be read, any character without a move (including ones outside SIGMA,
+//! such as bytes of a multibyte character in C) rejects, and it accepts if it stops in an accepting state.
+//! Moves into states that can never accept are left out, they reject just the same.
+
+use crate::diagram::{edges, symbol_ranges};
+use crate::dfa::Dfa;
+
+//words each language keeps for itself, a function can't be named any of them
+const RUST_KEYWORDS: [&str; 52] = [
+    "_", "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become",
+    "box", "do", "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+const C_KEYWORDS: [&str; 44] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum", "extern", "float",
+    "for", "goto", "if", "inline", "int", "long", "register", "restrict", "return", "short", "signed", "sizeof",
+    "static", "struct", "switch", "typedef", "union", "unsigned", "void", "volatile", "while", "_Alignas", "_Alignof",
+    "_Atomic", "_Bool", "_Complex", "_Generic", "_Imaginary", "_Noreturn", "_Static_assert", "_Thread_local",
+];
+const JS_KEYWORDS: [&str; 48] = [
+    "await", "break", "case", "catch", "class", "const", "continue", "debugger", "default", "delete", "do", "else",
+    "enum", "export", "extends", "false", "finally", "for", "function", "if", "import", "in", "instanceof", "new",
+    "null", "return", "super", "switch", "this", "throw", "true", "try", "typeof", "var", "void", "while", "with",
+    "yield", "let", "static", "implements", "interface", "package", "private", "protected", "public", "arguments", "eval",
+];
+
+/// For checking that a function name is not one of the language's own words
+/// - Input: The name, and the language (one of rust, c, or js)
+/// - Output: True if the name is reserved and the generated code would not compile
+pub fn is_reserved(name: &str, lang: &str) -> bool {
+    match lang {
+        "c" => C_KEYWORDS.contains(&name),
+        "js" => JS_KEYWORDS.contains(&name),
+        _ => RUST_KEYWORDS.contains(&name),
+    }
+}
+
+/// For grouping the moves that can still lead to acceptance into one per pair of states
+fn live_moves(dfa: &Dfa) -> Vec<(usize, usize, Vec<char>)> {
+    let live = dfa.live_states();
+    edges(dfa).into_iter().filter(|(_, to, _)| live[*to]).collect()
+}
+
+/// For writing the regex into a comment of the generated code
+/// Imported automata can hold any text as their pattern, and a newline (or in JavaScript a line or paragraph
+/// separator) would end the comment early, so those are written escaped the way Rust writes them in a string ("\n")
+/// - Input: The regex
+/// - Output: The text to put in the comment
+fn comment_text(pattern: &str) -> String {
+    pattern.chars()
+        .map(|c| if c.is_control() || c == '\u{2028}' || c == '\u{2029}' {c.escape_default().to_string()} else {c.to_string()})
+        .collect()
+}
+
+/// For writing the automaton as a Rust function using a match on (state, character)
+/// - Input: The Dfa, and the name of the function
+/// - Output: String holding the function, which needs nothing outside the standard library
+pub fn rust(dfa: &Dfa, name: &str) -> String {
+    let mut out = String::new();
+    if !dfa.pattern.is_empty() {
+        out.push_str(&format!("/// Generated from the regex {}\n", comment_text(&dfa.pattern)));
+    }
+    out.push_str("/// Returns true if the whole input is accepted\n");
+    out.push_str(&format!("pub fn {}(input: &str) -> bool {{\n", name));
+    let moves = live_moves(dfa);
+    if moves.is_empty() {
+        //no moves to make, so only the empty string can be accepted
+        if dfa.is_accepting(dfa.start) {
+            out.push_str("    input.is_empty()\n");
+        }
+        else {
+            out.push_str("    let _ = input;\n    false\n");
+        }
+        out.push_str("}\n");
+        return out;
+    }
+
+    out.push_str(&format!("    let mut state: usize = {};\n", dfa.start));
+    out.push_str("    for c in input.chars() {\n");
+    out.push_str("        state = match (state, c) {\n");
+    for (from, to, symbols) in moves {
+        let pieces: Vec<String> = symbol_ranges(&symbols).iter().map(|p| rust_pattern(p)).collect();
+        out.push_str(&format!("            ({}, {}) => {},\n", from, pieces.join(" | "), to));
+    }
+    out.push_str("            _ => return false,\n");
+    out.push_str("        };\n");
+    out.push_str("    }\n");
+    let accepting: Vec<String> = dfa.accepting.iter().map(|st| st.to_string()).collect();
+    out.push_str(&format!("    matches!(state, {})\n", accepting.join(" | ")));
+    out.push_str("}\n");
+    out
+}
+
+/// For writing a piece from symbol_ranges as a Rust char pattern, "a-z" is 'a'..='z'
+fn rust_pattern(piece: &str) -> String {
+    let chars: Vec<char> = piece.chars().collect();
+    if chars.len() == 3 && chars[1] == '-' {
+        format!("'{}'..='{}'", chars[0], chars[2])
+    }
+    else {
+        format!("'{}'", piece)
+    }
+}
+
+/// For writing the automaton as a C function using a switch on the state, reading the input a byte at a time
+/// - Input: The Dfa, and the name of the function
+/// - Output: String holding the function, which needs no headers, returning 1 to accept and 0 to reject
+pub fn c(dfa: &Dfa, name: &str) -> String {
+    let mut out = String::new();
+    if !dfa.pattern.is_empty() {
+        //a */ in the pattern would close the comment
+        out.push_str(&format!("/* Generated from the regex {} */\n", comment_text(&dfa.pattern).replace("*/", "*\\/")));
+    }
+    out.push_str("/* Returns 1 if the whole input is accepted, 0 if not */\n");
+    out.push_str(&format!("int {}(const char *input) {{\n", name));
+    let moves = live_moves(dfa);
+    if moves.is_empty() {
+        if dfa.is_accepting(dfa.start) {
+            out.push_str("    return input[0] == '\\0';\n}\n");
+        }
+        else {
+            out.push_str("    (void)input;\n    return 0;\n}\n");
+        }
+        return out;
+    }
+
+    out.push_str(&format!("    int state = {};\n", dfa.start));
+    out.push_str("    for (const char *p = input; *p != '\\0'; p++) {\n");
+    out.push_str("        char c = *p;\n");
+    out.push_str("        switch (state) {\n");
+    write_cases(&mut out, &moves, "'", "==");
+    out.push_str("        default:\n            return 0;\n        }\n    }\n");
+    let accepting: Vec<String> = dfa.accepting.iter().map(|st| format!("state == {}", st)).collect();
+    out.push_str(&format!("    return {};\n}}\n", accepting.join(" || ")));
+    out
+}
+
+/// For writing the automaton as a JavaScript function using a switch on the state, reading the input a character at a time
+/// - Input: The Dfa, and the name of the function
+/// - Output: String holding the function, returning true to accept and false to reject
+pub fn javascript(dfa: &Dfa, name: &str) -> String {
+    let mut out = String::new();
+    if !dfa.pattern.is_empty() {
+        out.push_str(&format!("// Generated from the regex {}\n", comment_text(&dfa.pattern)));
+    }
+    out.push_str("// Returns true if the whole input is accepted\n");
+    out.push_str(&format!("function {}(input) {{\n", name));
+    let moves = live_moves(dfa);
+    if moves.is_empty() {
+        let body = if dfa.is_accepting(dfa.start) {"input.length === 0"} else {"false"};
+        out.push_str(&format!("    return {};\n}}\n", body));
+        return out;
+    }
+
+    out.push_str(&format!("    let state = {};\n", dfa.start));
+    out.push_str("    for (const c of input) {\n");
+    out.push_str("        switch (state) {\n");
+    write_cases(&mut out, &moves, "\"", "===");
+    out.push_str("        default:\n            return false;\n        }\n    }\n");
+    let accepting: Vec<String> = dfa.accepting.iter().map(|st| format!("state === {}", st)).collect();
+    out.push_str(&format!("    return {};\n}}\n", accepting.join(" || ")));
+    out
+}
+
+/// For writing the cases of the switch in the C and JavaScript functions, which are written the same way
+/// Each state is a case that tries its moves in turn and rejects if none fits (a break leaves the switch)
+/// - Input: The output so far, the moves, and how the language quotes a character and tests equality
+/// - Output: None, the cases are added to the output
+fn write_cases(out: &mut String, moves: &[(usize, usize, Vec<char>)], quote: &str, equals: &str) {
+    let mut from = None;
+    for (i, (state, to, symbols)) in moves.iter().enumerate() {
+        if from != Some(*state) {
+            out.push_str(&format!("        case {}:\n", state));
+            from = Some(*state);
+        }
+        let tests: Vec<String> = symbol_ranges(symbols).iter().map(|piece| {
+            let chars: Vec<char> = piece.chars().collect();
+            if chars.len() == 3 && chars[1] == '-' {
+                format!("(c >= {q}{}{q} && c <= {q}{}{q})", chars[0], chars[2], q = quote)
+            }
+            else {
+                format!("c {} {q}{}{q}", equals, piece, q = quote)
+            }
+        }).collect();
+        out.push_str(&format!("            if ({}) {{ state = {}; break; }}\n", tests.join(" || "), to));
+        if moves.get(i + 1).is_none_or(|next| next.0 != *state) {
+            out.push_str(if quote == "'" {"            return 0;\n"} else {"            return false;\n"});
+        }
+    }
+}
+
+#[test]
+fn test_rust(){
+    use crate::SIGMA;
+
+    //\w\d+
+    let dfa = Dfa {
+        pattern: "\\w\\d+".to_string(),
+        alphabet: SIGMA.to_vec(),
+        transitions: vec![
+            SIGMA.iter().map(|c| if c.is_ascii_lowercase() {Some(1)} else {None}).collect(),
+            SIGMA.iter().map(|c| if c.is_ascii_digit() {Some(2)} else {None}).collect(),
+            SIGMA.iter().map(|c| if c.is_ascii_digit() || *c == ' ' {Some(2)} else {None}).collect(),
+        ],
+        start: 0,
+        accepting: vec![2],
+    };
+    let code = rust(&dfa, "is_id");
+    assert!(code.starts_with("/// Generated from the regex \\w\\d+\n"));
+    assert!(code.contains("pub fn is_id(input: &str) -> bool {\n    let mut state: usize = 0;\n"));
+    assert!(code.contains("            (0, 'a'..='z') => 1,\n            (1, '0'..='9') => 2,\n            (2, '0'..='9' | ' ') => 2,\n"));
+    assert!(code.ends_with("            _ => return false,\n        };\n    }\n    matches!(state, 2)\n}\n"));
+
+    //a pattern from an imported file can hold a newline, it stays inside the comment
+    let imported = Dfa { pattern: "a\nfn oops() {}".to_string(), ..dfa };
+    assert!(rust(&imported, "is_id").starts_with("/// Generated from the regex a\\nfn oops() {}\n/// Returns true"));
+
+    //nothing accepted
+    let empty = Dfa { pattern: String::new(), alphabet: SIGMA.to_vec(), transitions: vec![vec![None; SIGMA.len()]], start: 0, accepting: vec![] };
+    assert_eq!(rust(&empty, "f"), "/// Returns true if the whole input is accepted\npub fn f(input: &str) -> bool {\n    let _ = input;\n    false\n}\n");
+}
+
+#[test]
+fn test_c_and_javascript(){
+    use crate::SIGMA;
+
+    //a\d*: a, then digits with a space allowed among them
+    let mut first = vec![None; SIGMA.len()];
+    first[0] = Some(1);
+    let dfa = Dfa {
+        pattern: String::new(),
+        alphabet: SIGMA.to_vec(),
+        transitions: vec![first, SIGMA.iter().map(|c| if c.is_ascii_digit() || *c == ' ' {Some(1)} else {None}).collect()],
+        start: 0,
+        accepting: vec![1],
+    };
+    assert_eq!(c(&dfa, "match"), "\
+/* Returns 1 if the whole input is accepted, 0 if not */
+int match(const char *input) {
+    int state = 0;
+    for (const char *p = input; *p != '\\0'; p++) {
+        char c = *p;
+        switch (state) {
+        case 0:
+            if (c == 'a') { state = 1; break; }
+            return 0;
+        case 1:
+            if ((c >= '0' && c <= '9') || c == ' ') { state = 1; break; }
+            return 0;
+        default:
+            return 0;
+        }
+    }
+    return state == 1;
+}
+");
+    let js = javascript(&dfa, "match");
+    assert!(js.starts_with("// Returns true if the whole input is accepted\nfunction match(input) {\n    let state = 0;\n    for (const c of input) {\n"));
+    assert!(js.contains("            if ((c >= \"0\" && c <= \"9\") || c === \" \") { state = 1; break; }\n            return false;\n"));
+    assert!(js.ends_with("    return state === 1;\n}\n"));
+
+    //patterns from imported files can hold text that would end either comment
+    let imported = Dfa { pattern: "a*/b\u{2028}c".to_string(), ..dfa };
+    assert!(c(&imported, "match").starts_with("/* Generated from the regex a*\\/b\\u{2028}c */\n"));
+    assert!(javascript(&imported, "match").starts_with("// Generated from the regex a*/b\\u{2028}c\n"));
+}
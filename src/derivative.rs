@@ -0,0 +1,96 @@
+//! # Brzozowski derivatives
+//! The derivative of a regex by a symbol c is a regex for what can follow c: the strings w where cw is matched.
+//! A string is matched when taking the derivative by each of its symbols in turn leaves a regex that matches ε.
+//! Derivatives are taken straight on the tree, so '&' and '~' need nothing special:
+//!     d(X&Y) = d(X)&d(Y)        d(X~Y) = d(X)~d(Y)
+//! Every derivative is a state of a Dfa. Built with the constructors in simplify.rs, which put unions in one order
+//! and drop repeats, a regex only has finitely many different derivatives, so exploring them gives a complete Dfa.
+//! This is a second construction, separate from thompson.rs and nfa.rs, for checking them against.
+
+use std::collections::HashMap;
+
+use crate::dfa::Dfa;
+use crate::regex::{nullable, Regex};
+use crate::simplify::{and, concat, minus, union};
+use crate::SIGMA;
+
+/// For taking the derivative of a tree by one symbol
+/// - Input: The tree, and the symbol
+/// - Output: A tree matching w exactly when the original matches the symbol followed by w
+pub fn derive(regex: &Regex, c: char) -> Regex {
+    match regex {
+        Regex::Symbols(symbols) => if symbols.contains(&c) {Regex::Epsilon} else {Regex::Empty},
+        Regex::Epsilon | Regex::Empty => Regex::Empty,
+        Regex::Concat(parts) => {
+            //d(XY) = d(X)Y, or also d(Y) when X can match ε
+            let rest = concat(parts[1..].to_vec());
+            let first = concat(vec![derive(&parts[0], c), rest.clone()]);
+            if nullable(&parts[0]) {
+                union(vec![first, derive(&rest, c)])
+            }
+            else {
+                first
+            }
+        }
+        Regex::Union(options) => union(options.iter().map(|o| derive(o, c)).collect()),
+        Regex::Star(inner) | Regex::Plus(inner) => concat(vec![derive(inner, c), Regex::Star(inner.clone())]),
+        Regex::And(left, right) => and(derive(left, c), derive(right, c)),
+        Regex::Minus(left, right) => minus(derive(left, c), derive(right, c)),
+    }
+}
+
+/// For building a Dfa whose states are the derivatives of the tree, only the ones some string reaches
+/// A derivative that simplifies to the empty regex becomes a missing transition rather than a dead state, but one
+/// that matches nothing without simplifying that far (like b&c, the derivative of ab&ac by a) still gets a state that never accepts
+/// - Input: The tree, and the pattern it came from
+/// - Output: The Dfa, with the tree itself as state 0
+pub fn derivative_dfa(regex: &Regex, pattern: &str) -> Dfa {
+    let mut states = vec![regex.clone()];
+    let mut index: HashMap<Regex, usize> = HashMap::new();
+    index.insert(regex.clone(), 0);
+    let mut transitions = Vec::new();
+
+    //states are explored in the order they are found
+    let mut next_state = 0;
+    while next_state < states.len() {
+        let mut row = Vec::new();
+        for c in SIGMA.iter() {
+            let next = derive(&states[next_state], *c);
+            if next == Regex::Empty {
+                row.push(None);
+                continue;
+            }
+            let target = *index.entry(next.clone()).or_insert_with(|| {
+                states.push(next);
+                states.len() - 1
+            });
+            row.push(Some(target));
+        }
+        transitions.push(row);
+        next_state += 1;
+    }
+
+    Dfa {
+        pattern: pattern.to_string(),
+        alphabet: SIGMA.to_vec(),
+        transitions,
+        start: 0,
+        accepting: (0..states.len()).filter(|st| nullable(&states[*st])).collect(),
+    }
+}
+
+#[test]
+fn test_derive(){
+    use crate::regex::to_pattern;
+    use crate::tree;
+
+    assert_eq!(to_pattern(&derive(&tree("ab*"), 'a')), "b*");
+    assert_eq!(derive(&tree("ab*"), 'b'), Regex::Empty);
+    assert_eq!(to_pattern(&derive(&tree("(ab)*"), 'a')), "b(ab)*");
+    let keywords = derivative_dfa(&tree("\\w+~(if|in)"), "");
+    assert!(keywords.accepts("iff") && keywords.accepts("i") && !keywords.accepts("in"));
+    assert!(derivative_dfa(&tree("(a|b)*&\\w*a\\w*"), "").accepts("bba"));
+
+    //(a|b)*a(a|b) has the 4 states of the smallest Dfa
+    assert_eq!(derivative_dfa(&tree("(a|b)*a(a|b)"), "").transitions.len(), 4);
+}
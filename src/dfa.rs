@@ -0,0 +1,672 @@
+//! # Deterministic Finite Automaton
+//! A typed form of the transition table built by parse_regex.
+//! The table form (Vec<Vec<String>>) is what check_string and print_state_diagram work with,
+//! this form is what gets saved, loaded, and compared.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::SIGMA;
+
+/// A DFA with an explicit alphabet, start state, and set of accepting states
+/// - pattern: The regular expression the automaton came from (may be empty if unknown)
+/// - alphabet: The symbols the automaton reads, transitions are indexed by position in this vec
+/// - transitions: For every state, the state reached on each alphabet symbol (None if there is no transition)
+/// - start: The state the automaton begins in
+/// - accepting: Sorted list of the accepting states
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dfa {
+    pub pattern: String,
+    pub alphabet: Vec<char>,
+    pub transitions: Vec<Vec<Option<usize>>>,
+    pub start: usize,
+    pub accepting: Vec<usize>,
+}
+
+impl Dfa {
+    /// For turning a transition table from parse_regex into a Dfa
+    /// States the table points to but has no row for are kept as states with no transitions, the same way check_string treats them
+    /// - Input: The regex the table was built from, and the transition table
+    /// - Output: The equivalent Dfa, with SIGMA as its alphabet and 0 as its start state
+    pub fn from_table(pattern: &str, table: &[Vec<String>]) -> Dfa {
+        let rows = table.len() - 1; //the last row holds the accept states
+        let mut transitions = Vec::new();
+        for row in &table[..rows] {
+            let mut trans = Vec::new();
+            for cell in row {
+                if cell == " " {
+                    trans.push(None);
+                }
+                else {
+                    trans.push(Some(cell.parse::<usize>().unwrap()));
+                }
+            }
+            transitions.push(trans);
+        }
+
+        //make room for any state that is only ever a target
+        let mut state_count = rows.max(1);
+        for row in &transitions {
+            for target in row.iter().flatten() {
+                state_count = state_count.max(*target + 1);
+            }
+        }
+        let mut accepting = Vec::new();
+        for st in &table[rows] {
+            if st == "X" {continue;} //marker for the accept state row
+            let st = st.parse::<usize>().unwrap();
+            state_count = state_count.max(st + 1);
+            if !accepting.contains(&st) {
+                accepting.push(st);
+            }
+        }
+        accepting.sort_unstable();
+        while transitions.len() < state_count {
+            transitions.push(vec![None; SIGMA.len()]);
+        }
+
+        Dfa {
+            pattern: pattern.to_string(),
+            alphabet: SIGMA.to_vec(),
+            transitions,
+            start: 0,
+            accepting,
+        }
+    }
+
+    /// For turning a Dfa back into the transition table form used by check_string and print_state_diagram
+    /// The start state is swapped with state 0, since the table always starts in state 0
+    /// Every alphabet symbol must be in SIGMA
+    /// - Input: None
+    /// - Output: The transition table
+    pub fn to_table(&self) -> Vec<Vec<String>> {
+        //state numbers in the table, the start state trades places with state 0
+        let rename = |st: usize| -> usize {
+            if st == self.start {0}
+            else if st == 0 {self.start}
+            else {st}
+        };
+        let mut table = vec![Vec::new(); self.transitions.len()];
+        for (state, row) in self.transitions.iter().enumerate() {
+            let mut new_row = vec![" ".to_string(); SIGMA.len()];
+            for (i, target) in row.iter().enumerate() {
+                if let Some(target) = target {
+                    let column = SIGMA.iter().position(|&x| x == self.alphabet[i]).unwrap();
+                    new_row[column] = rename(*target).to_string();
+                }
+            }
+            table[rename(state)] = new_row;
+        }
+        let mut accept_states = vec!["X".to_string()];
+        for st in &self.accepting {
+            accept_states.push(rename(*st).to_string());
+        }
+        table.push(accept_states);
+        table
+    }
+
+    /// For following one move of the automaton
+    /// - Input: The current state and the next input character
+    /// - Output: The state moved to, or None if there is no move (the string is rejected)
+    pub fn step(&self, state: usize, symbol: char) -> Option<usize> {
+        let i = self.alphabet.iter().position(|&x| x == symbol)?;
+        self.transitions[state][i]
+    }
+
+    /// For checking if a state is an accepting state
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.accepting.binary_search(&state).is_ok()
+    }
+
+    /// For running the automaton on a whole string, the same way check_string does
+    /// - Input: The string
+    /// - Output: Boolean, true if the string is accepted
+    pub fn accepts(&self, input: &str) -> bool {
+        let mut state = self.start;
+        for c in input.chars() {
+            match self.step(state, c) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        self.is_accepting(state)
+    }
+
+    /// For running two automata side by side (the product construction)
+    /// A missing move in either automaton is treated as moving to a dead state that never accepts
+    /// - Input: The other Dfa, and a function deciding if a pair of states accepts given whether each one accepts
+    /// - Output: A Dfa over SIGMA whose states are the reachable pairs of states
+    pub fn product(&self, other: &Dfa, accept: fn(bool, bool) -> bool) -> Dfa {
+        //None stands for the dead state, the pair of two dead states is only kept if it accepts
+        type Pair = (Option<usize>, Option<usize>);
+        let keep_dead = accept(false, false);
+        let mut pairs: Vec<Pair> = vec![(Some(self.start), Some(other.start))];
+        let mut index_of: HashMap<Pair, usize> = HashMap::new();
+        index_of.insert(pairs[0], 0);
+        let mut transitions = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front() {
+            let (a, b) = pairs[current];
+            let mut row = vec![None; SIGMA.len()];
+            for (i, sym) in SIGMA.iter().enumerate() {
+                let next: Pair = (a.and_then(|st| self.step(st, *sym)), b.and_then(|st| other.step(st, *sym)));
+                if next == (None, None) && !keep_dead {
+                    continue;
+                }
+                let index = match index_of.get(&next) {
+                    Some(index) => *index,
+                    None => {
+                        pairs.push(next);
+                        index_of.insert(next, pairs.len() - 1);
+                        queue.push_back(pairs.len() - 1);
+                        pairs.len() - 1
+                    }
+                };
+                row[i] = Some(index);
+            }
+            transitions.push(row); //pairs leave the queue in the order they were numbered
+        }
+
+        let mut accepting = Vec::new();
+        for (i, (a, b)) in pairs.iter().enumerate() {
+            let a_accepts = a.is_some_and(|st| self.is_accepting(st));
+            let b_accepts = b.is_some_and(|st| other.is_accepting(st));
+            if accept(a_accepts, b_accepts) {
+                accepting.push(i);
+            }
+        }
+
+        Dfa {
+            pattern: String::new(),
+            alphabet: SIGMA.to_vec(),
+            transitions,
+            start: 0,
+            accepting,
+        }
+    }
+
+    /// For building an automaton that accepts exactly the strings over SIGMA this one rejects
+    /// Missing moves go to a new dead state first, so every string ends somewhere, then accepting and rejecting states swap
+    /// - Input: None
+    /// - Output: The complement Dfa, over SIGMA
+    pub fn complement(&self) -> Dfa {
+        let dead = self.transitions.len();
+        let mut transitions = Vec::new();
+        for state in 0..self.transitions.len() {
+            transitions.push(SIGMA.iter().map(|sym| Some(self.step(state, *sym).unwrap_or(dead))).collect());
+        }
+        transitions.push(vec![Some(dead); SIGMA.len()]);
+        let accepting = (0..transitions.len()).filter(|st| !self.is_accepting(*st)).collect();
+
+        Dfa {
+            pattern: String::new(),
+            alphabet: SIGMA.to_vec(),
+            transitions,
+            start: self.start,
+            accepting,
+        }
+    }
+
+    /// For finding the states that can still reach an accepting state
+    /// - Input: None
+    /// - Output: For each state, true if some string leads from it to an accepting state
+    pub fn live_states(&self) -> Vec<bool> {
+        let mut live = vec![false; self.transitions.len()];
+        for st in &self.accepting {
+            live[*st] = true;
+        }
+        //keep marking states with a move into a live state until nothing changes
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (state, row) in self.transitions.iter().enumerate() {
+                if !live[state] && row.iter().flatten().any(|next| live[*next]) {
+                    live[state] = true;
+                    changed = true;
+                }
+            }
+        }
+        live
+    }
+
+    /// For putting the useful states (reachable from the start and able to reach an accepting state) in order
+    /// so every move between them goes forward, which is only possible when they have no loop
+    /// - Input: None
+    /// - Output: The useful states in that order, or None if there is a loop and so infinitely many accepted strings
+    fn useful_order(&self) -> Option<Vec<usize>> {
+        let live = self.live_states();
+        let mut useful = vec![false; self.transitions.len()];
+        let mut stack = vec![self.start];
+        while let Some(state) = stack.pop() {
+            if live[state] && !useful[state] {
+                useful[state] = true;
+                stack.extend(self.transitions[state].iter().flatten());
+            }
+        }
+
+        //repeatedly take a state no remaining state moves into (Kahn's algorithm)
+        let mut incoming = vec![0; self.transitions.len()];
+        for (state, row) in self.transitions.iter().enumerate() {
+            if useful[state] {
+                for next in row.iter().flatten() {
+                    if useful[*next] {
+                        incoming[*next] += 1;
+                    }
+                }
+            }
+        }
+        let mut ready: Vec<usize> = (0..self.transitions.len()).filter(|st| useful[*st] && incoming[*st] == 0).collect();
+        let mut order = Vec::new();
+        while let Some(state) = ready.pop() {
+            order.push(state);
+            for next in self.transitions[state].iter().flatten() {
+                if useful[*next] {
+                    incoming[*next] -= 1;
+                    if incoming[*next] == 0 {
+                        ready.push(*next);
+                    }
+                }
+            }
+        }
+        if order.len() == useful.iter().filter(|u| **u).count() {
+            Some(order)
+        }
+        else {
+            None
+        }
+    }
+
+    /// For checking whether the automaton accepts only a limited number of strings
+    /// - Input: None
+    /// - Output: True if the accepted strings are finite (including none at all)
+    pub fn is_finite(&self) -> bool {
+        self.useful_order().is_some()
+    }
+
+    /// For finding a longest accepted string, when there is one
+    /// Of the longest strings the first in alphabet order is found
+    /// - Input: None
+    /// - Output: The string, or None if nothing is accepted or accepted strings can be any length
+    pub fn longest_accepted(&self) -> Option<String> {
+        let order = self.useful_order()?;
+        //longest[state] is the length of the longest string from there to an accepting state,
+        //and first[state] is the move it starts with (None if it is the empty string)
+        let mut longest: Vec<Option<usize>> = vec![None; self.transitions.len()];
+        let mut first: Vec<Option<(char, usize)>> = vec![None; self.transitions.len()];
+        for state in order.iter().rev() {
+            if self.is_accepting(*state) {
+                longest[*state] = Some(0);
+            }
+            for (i, target) in self.transitions[*state].iter().enumerate() {
+                if let Some(next) = target {
+                    if let Some(length) = longest[*next] {
+                        if longest[*state].is_none_or(|best| length + 1 > best) {
+                            longest[*state] = Some(length + 1);
+                            first[*state] = Some((self.alphabet[i], *next));
+                        }
+                    }
+                }
+            }
+        }
+
+        //follow the chosen moves from the start
+        longest[self.start]?;
+        let mut string = String::new();
+        let mut step = first[self.start];
+        while let Some((symbol, next)) = step {
+            string.push(symbol);
+            step = first[next];
+        }
+        Some(string)
+    }
+
+    /// For listing accepted strings in shortlex order (shortest first, then in alphabet order)
+    /// Works one length at a time, walking depth first and only taking moves that can still end in an accepting state
+    /// after exactly the symbols left, so every string built is accepted and the walk stops once max_count are found
+    /// - Input: The longest string to list, and the most strings to list
+    /// - Output: The accepted strings
+    pub fn enumerate(&self, max_len: usize, max_count: usize) -> Vec<String> {
+        //finishes[r][state] says whether some string of exactly r symbols leads from state to an accepting state
+        let mut finishes = vec![(0..self.transitions.len()).map(|st| self.is_accepting(st)).collect::<Vec<bool>>()];
+        for r in 1..=max_len {
+            let row = self.transitions.iter()
+                .map(|moves| moves.iter().flatten().any(|next| finishes[r - 1][*next]))
+                .collect();
+            finishes.push(row);
+        }
+
+        let mut found = Vec::new();
+        for length in 0..=max_len {
+            if found.len() == max_count {
+                break;
+            }
+            if !finishes[length][self.start] {
+                continue;
+            }
+            let mut string = Vec::new();
+            //the states on the way to the end of string, each with the next symbol to try from it
+            let mut path = vec![(self.start, 0)];
+            while let Some((state, from)) = path.pop() {
+                let left = length - string.len();
+                if left == 0 {
+                    found.push(string.iter().collect());
+                    if found.len() == max_count {
+                        break;
+                    }
+                    string.pop();
+                    continue;
+                }
+                let step = (from..self.alphabet.len()).find_map(|i| match self.transitions[state][i] {
+                    Some(next) if finishes[left - 1][next] => Some((i, next)),
+                    _ => None,
+                });
+                match step {
+                    Some((i, next)) => {
+                        path.push((state, i + 1));
+                        path.push((next, 0));
+                        string.push(self.alphabet[i]);
+                    }
+                    None => {
+                        string.pop();
+                    }
+                }
+            }
+        }
+        found
+    }
+
+    /// For counting the accepted strings of each length, working back from the accepting states
+    /// Counts too big for a u128 stay at u128::MAX
+    /// - Input: The longest length to count
+    /// - Output: counts[k][state] is the number of strings of length k that lead from state to an accepting state
+    pub fn count_accepted(&self, max_len: usize) -> Vec<Vec<u128>> {
+        let mut counts = vec![(0..self.transitions.len()).map(|st| self.is_accepting(st) as u128).collect::<Vec<u128>>()];
+        for k in 1..=max_len {
+            let shorter = &counts[k - 1];
+            let row = self.transitions.iter()
+                .map(|moves| moves.iter().flatten().fold(0u128, |total, next| total.saturating_add(shorter[*next])))
+                .collect();
+            counts.push(row);
+        }
+        counts
+    }
+
+    /// For finding a shortest accepted string with a breadth first search
+    /// Symbols are tried in alphabet order, so of the shortest strings the first in that order is found
+    /// - Input: None
+    /// - Output: The string, or None if the automaton accepts nothing
+    pub fn shortest_accepted(&self) -> Option<String> {
+        //how each state was first reached: (previous state, symbol)
+        let mut reached_from: Vec<Option<(usize, char)>> = vec![None; self.transitions.len()];
+        let mut seen = vec![false; self.transitions.len()];
+        seen[self.start] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(self.start);
+
+        while let Some(state) = queue.pop_front() {
+            if self.is_accepting(state) {
+                //walk back to the start to spell out the string
+                let mut symbols = Vec::new();
+                let mut current = state;
+                while let Some((previous, symbol)) = reached_from[current] {
+                    symbols.push(symbol);
+                    current = previous;
+                }
+                return Some(symbols.iter().rev().collect());
+            }
+            for (i, target) in self.transitions[state].iter().enumerate() {
+                if let Some(next) = target {
+                    if !seen[*next] {
+                        seen[*next] = true;
+                        reached_from[*next] = Some((state, self.alphabet[i]));
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// For making the Dfa with the fewest states that accepts the same strings (Moore's partition refinement)
+    /// States that can't be reached, or can't reach an accepting state, are dropped since a missing move rejects the same way,
+    /// then states are split into classes by where their moves lead until no class splits, and each class becomes one state
+    /// - Input: None
+    /// - Output: The minimal Dfa, states numbered in the order a breadth first search from the start finds them
+    pub fn minimize(&self) -> Dfa {
+        let live = self.live_states();
+        let mut keep = vec![false; self.transitions.len()];
+        let mut queue = VecDeque::new();
+        if live[self.start] {
+            keep[self.start] = true;
+            queue.push_back(self.start);
+        }
+        while let Some(state) = queue.pop_front() {
+            for next in self.transitions[state].iter().flatten() {
+                if live[*next] && !keep[*next] {
+                    keep[*next] = true;
+                    queue.push_back(*next);
+                }
+            }
+        }
+        let target = |state: usize, i: usize| self.transitions[state][i].filter(|to| keep[*to]);
+
+        //start from accepting and not accepting, and split classes until the number of them stops growing
+        let mut class: Vec<usize> = (0..self.transitions.len()).map(|st| self.is_accepting(st) as usize).collect();
+        let mut count = 0;
+        loop {
+            let mut ids: HashMap<(usize, Vec<Option<usize>>), usize> = HashMap::new();
+            let mut next = vec![0; self.transitions.len()];
+            for state in (0..self.transitions.len()).filter(|st| keep[*st]) {
+                let moves = (0..self.alphabet.len()).map(|i| target(state, i).map(|to| class[to])).collect();
+                let size = ids.len();
+                next[state] = *ids.entry((class[state], moves)).or_insert(size);
+            }
+            class = next;
+            if ids.len() == count {
+                break;
+            }
+            count = ids.len();
+        }
+
+        //number the classes from the start, taking the first state found in each as its representative
+        let mut number: HashMap<usize, usize> = HashMap::new();
+        let mut transitions = Vec::new();
+        let mut accepting = Vec::new();
+        let mut queue = VecDeque::new();
+        if keep[self.start] {
+            number.insert(class[self.start], 0);
+            queue.push_back(self.start);
+        }
+        while let Some(state) = queue.pop_front() {
+            if self.is_accepting(state) {
+                accepting.push(transitions.len());
+            }
+            let row = (0..self.alphabet.len()).map(|i| target(state, i).map(|to| {
+                let size = number.len();
+                *number.entry(class[to]).or_insert_with(|| {
+                    queue.push_back(to);
+                    size
+                })
+            })).collect();
+            transitions.push(row);
+        }
+        if transitions.is_empty() {
+            //no strings are accepted, which only needs a start state with no moves
+            transitions.push(vec![None; self.alphabet.len()]);
+        }
+
+        Dfa {
+            pattern: self.pattern.clone(),
+            alphabet: self.alphabet.clone(),
+            transitions,
+            start: 0,
+            accepting,
+        }
+    }
+}
+
+#[test]
+fn test_from_table(){
+    //a*b, with the 'b' transition pointing at a state with no row of its own
+    let mut row = vec![" ".to_string(); SIGMA.len()];
+    row[0] = "0".to_string();
+    row[1] = "1".to_string();
+    let table = vec![row, vec!["X".to_string(), "1".to_string()]];
+    let dfa = Dfa::from_table("a*b", &table);
+
+    assert_eq!(dfa.transitions.len(), 2);
+    assert_eq!(dfa.transitions[0][0], Some(0));
+    assert_eq!(dfa.transitions[0][1], Some(1));
+    assert_eq!(dfa.transitions[1], vec![None; SIGMA.len()]);
+    assert_eq!(dfa.accepting, vec![1]);
+}
+
+#[test]
+fn test_to_table(){
+    //'ab' with the start state numbered 2
+    let dfa = Dfa {
+        pattern: "ab".to_string(),
+        alphabet: vec!['b', 'a'],
+        transitions: vec![vec![None, None], vec![Some(0), None], vec![None, Some(1)]],
+        start: 2,
+        accepting: vec![0],
+    };
+    let table = dfa.to_table();
+
+    assert_eq!(table[0][0], "1"); //0 -a-> 1
+    assert_eq!(table[1][1], "2"); //1 -b-> 2
+    assert_eq!(table[3], vec!["X".to_string(), "2".to_string()]);
+    assert!(crate::check_string("ab", &table));
+    assert!(!crate::check_string("ba", &table));
+}
+
+#[test]
+fn test_product(){
+    //a*b against a+b, they only differ on "b"
+    let mut row = vec![" ".to_string(); SIGMA.len()];
+    row[0] = "0".to_string();
+    row[1] = "1".to_string();
+    let a_star_b = Dfa::from_table("a*b", &[row, vec!["X".to_string(), "1".to_string()]]);
+    let mut first = vec![" ".to_string(); SIGMA.len()];
+    first[0] = "1".to_string();
+    let mut second = vec![" ".to_string(); SIGMA.len()];
+    second[0] = "1".to_string();
+    second[1] = "2".to_string();
+    let a_plus_b = Dfa::from_table("a+b", &[first, second, vec!["X".to_string(), "2".to_string()]]);
+
+    let difference = a_star_b.product(&a_plus_b, |x, y| x != y);
+    assert_eq!(difference.shortest_accepted(), Some("b".to_string()));
+    let both = a_star_b.product(&a_plus_b, |x, y| x && y);
+    assert_eq!(both.shortest_accepted(), Some("ab".to_string()));
+    assert!(both.accepts("aaab"));
+    assert!(!both.accepts("b"));
+
+    //a*b is not inside a+b, but a+b is inside a*b
+    assert_eq!(a_star_b.product(&a_plus_b.complement(), |x, y| x && y).shortest_accepted(), Some("b".to_string()));
+    assert_eq!(a_plus_b.product(&a_star_b.complement(), |x, y| x && y).shortest_accepted(), None);
+}
+
+#[test]
+fn test_complement(){
+    let mut row = vec![" ".to_string(); SIGMA.len()];
+    row[0] = "1".to_string();
+    let just_a = Dfa::from_table("a", &[row, vec!["X".to_string(), "1".to_string()]]);
+    let not_a = just_a.complement();
+    assert!(!not_a.accepts("a"));
+    assert!(not_a.accepts(""));
+    assert!(not_a.accepts("aa"));
+    assert!(not_a.accepts("b 9"));
+    assert_eq!(not_a.shortest_accepted(), Some(String::new()));
+}
+
+#[test]
+fn test_enumerate(){
+    //(a|b)b*, from the start a or b goes to 1 and 1 loops on b
+    let mut first = vec![" ".to_string(); SIGMA.len()];
+    first[0] = "1".to_string();
+    first[1] = "1".to_string();
+    let mut second = vec![" ".to_string(); SIGMA.len()];
+    second[1] = "1".to_string();
+    let dfa = Dfa::from_table("", &[first, second, vec!["X".to_string(), "1".to_string()]]);
+
+    assert_eq!(dfa.enumerate(2, 100), vec!["a", "b", "ab", "bb"]);
+    assert_eq!(dfa.enumerate(5, 3), vec!["a", "b", "ab"]);
+    assert_eq!(dfa.enumerate(0, 100), Vec::<String>::new());
+    assert_eq!(dfa.live_states(), vec![true, true]);
+
+    //every string of SIGMA, far too many to list them all so the walk has to stop at max_count
+    let every = Dfa::from_table("", &[vec!["0".to_string(); SIGMA.len()], vec!["X".to_string(), "0".to_string()]]);
+    assert_eq!(every.enumerate(200, 4), vec!["", "a", "b", "c"]);
+    assert_eq!(every.enumerate(200, 40)[37..], [" ", "aa", "ab"]);
+}
+
+#[test]
+fn test_count_accepted(){
+    //\d+ has 10 strings of length 1 and 100 of length 2
+    let digits: Vec<String> = SIGMA.iter().map(|c| if c.is_ascii_digit() {"1".to_string()} else {" ".to_string()}).collect();
+    let dfa = Dfa::from_table("\\d+", &[digits.clone(), digits, vec!["X".to_string(), "1".to_string()]]);
+    let counts = dfa.count_accepted(2);
+    assert_eq!(counts.iter().map(|row| row[0]).collect::<Vec<u128>>(), vec![0, 10, 100]);
+    assert_eq!(counts[0], vec![0, 1]);
+
+    //(\w|\d| )* runs past a u128 and stays at the top
+    let all = vec!["0".to_string(); SIGMA.len()];
+    let dfa = Dfa::from_table("", &[all, vec!["X".to_string(), "0".to_string()]]);
+    assert_eq!(dfa.count_accepted(30)[30][0], u128::MAX);
+}
+
+#[test]
+fn test_finite(){
+    //ab|c, finite with ab the longest
+    let mut first = vec![" ".to_string(); SIGMA.len()];
+    first[0] = "1".to_string();
+    first[2] = "2".to_string();
+    let mut second = vec![" ".to_string(); SIGMA.len()];
+    second[1] = "2".to_string();
+    let dfa = Dfa::from_table("ab|c", &[first, second, vec![" ".to_string(); SIGMA.len()], vec!["X".to_string(), "2".to_string()]]);
+    assert!(dfa.is_finite());
+    assert_eq!(dfa.longest_accepted(), Some("ab".to_string()));
+
+    //a loop on a dead state doesn't make it infinite, a loop on the way to acceptance does
+    let mut looping = dfa.clone();
+    looping.transitions.push(vec![None; SIGMA.len()]);
+    looping.transitions[3][0] = Some(3);
+    looping.transitions[0][3] = Some(3);
+    assert!(looping.is_finite());
+    looping.transitions[1][0] = Some(1);
+    assert!(!looping.is_finite());
+    assert_eq!(looping.longest_accepted(), None);
+
+    //nothing accepted
+    let empty = Dfa::from_table("", &[vec![" ".to_string(); SIGMA.len()], vec!["X".to_string()]]);
+    assert!(empty.is_finite());
+    assert_eq!(empty.longest_accepted(), None);
+}
+
+#[test]
+fn test_minimize(){
+    //(a|b)*a over {a, b}, built with a redundant copy of each state and an unreachable state
+    let dfa = Dfa {
+        pattern: String::new(),
+        alphabet: vec!['a', 'b'],
+        transitions: vec![
+            vec![Some(1), Some(2)],
+            vec![Some(3), Some(0)],
+            vec![Some(3), Some(2)],
+            vec![Some(1), Some(2)],
+            vec![Some(0), Some(0)],
+        ],
+        start: 0,
+        accepting: vec![1, 3],
+    };
+    let min = dfa.minimize();
+    assert_eq!(min.transitions, vec![vec![Some(1), Some(0)], vec![Some(1), Some(0)]]);
+    assert_eq!(min.accepting, vec![1]);
+    assert_eq!(dfa.product(&min, |a, b| a != b).shortest_accepted(), None);
+
+    //no strings accepted, and moves into dead states are dropped
+    let dead = Dfa { accepting: vec![], ..dfa };
+    assert_eq!(dead.minimize().transitions, vec![vec![None, None]]);
+}
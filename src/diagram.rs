@@ -0,0 +1,191 @@
+//! # State diagram output formats
+//! print_state_diagram writes Graphviz, these write the same automaton for other renderers (Mermaid, PlantUML, and LaTeX TikZ).
+//! Every format draws one edge per pair of states, labelled with all the symbols that make that move.
+
+use crate::dfa::Dfa;
+use crate::SIGMA;
+
+/// For grouping the moves of a Dfa into one edge per pair of states
+/// - Input: The Dfa
+/// - Output: (from, to, symbols) for every pair of states with a move between them, symbols in SIGMA order
+pub fn edges(dfa: &Dfa) -> Vec<(usize, usize, Vec<char>)> {
+    let mut edges: Vec<(usize, usize, Vec<char>)> = Vec::new();
+    for (from, row) in dfa.transitions.iter().enumerate() {
+        for (i, target) in row.iter().enumerate() {
+            if let Some(to) = target {
+                match edges.iter_mut().find(|(f, t, _)| *f == from && *t == *to) {
+                    Some(edge) => edge.2.push(dfa.alphabet[i]),
+                    None => edges.push((from, *to, vec![dfa.alphabet[i]])),
+                }
+            }
+        }
+    }
+    for edge in &mut edges {
+        edge.2.sort_by_key(|c| SIGMA.iter().position(|x| x == c));
+    }
+    edges
+}
+
+/// For shortening a list of symbols into ranges, so "abcd" becomes "a-d"
+/// Only runs of three or more letters or digits are joined
+/// - Input: Symbols in SIGMA order
+/// - Output: The pieces of the label, each a single symbol or a range like "a-z"
+pub fn symbol_ranges(symbols: &[char]) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut i = 0;
+    while i < symbols.len() {
+        //extend the run while the next symbol follows on in the same class
+        let mut end = i;
+        while end + 1 < symbols.len() && symbols[end + 1] as u32 == symbols[end] as u32 + 1
+            && symbols[end].is_ascii_digit() == symbols[end + 1].is_ascii_digit() && symbols[end + 1] != ' ' {
+            end += 1;
+        }
+        if end - i >= 2 {
+            pieces.push(format!("{}-{}", symbols[i], symbols[end]));
+            i = end + 1;
+        }
+        else {
+            pieces.push(symbols[i].to_string());
+            i += 1;
+        }
+    }
+    pieces
+}
+
+/// For writing a symbol label where a plain space would be lost
+/// - Input: Symbols in SIGMA order
+/// - Output: The label, such as "a-z, space"
+pub fn label(symbols: &[char]) -> String {
+    let pieces: Vec<String> = symbol_ranges(symbols).iter().map(|p| if p == " " {"space".to_string()} else {p.clone()}).collect();
+    pieces.join(", ")
+}
+
+/// For writing the automaton as a Mermaid state diagram
+/// - Input: The Dfa
+/// - Output: String holding the stateDiagram-v2 definition
+pub fn mermaid(dfa: &Dfa) -> String {
+    let mut out = String::from("stateDiagram-v2\n");
+    out.push_str(&format!("    [*] --> q{}\n", dfa.start));
+    for (from, to, symbols) in edges(dfa) {
+        out.push_str(&format!("    q{} --> q{}: {}\n", from, to, label(&symbols)));
+    }
+    for st in &dfa.accepting {
+        out.push_str(&format!("    q{} --> [*]\n", st));
+    }
+    out
+}
+
+/// For writing the automaton as a PlantUML state diagram
+/// - Input: The Dfa
+/// - Output: String holding the @startuml ... @enduml definition
+pub fn plantuml(dfa: &Dfa) -> String {
+    let mut out = String::from("@startuml\n");
+    out.push_str(&format!("[*] --> q{}\n", dfa.start));
+    for (from, to, symbols) in edges(dfa) {
+        out.push_str(&format!("q{} --> q{} : {}\n", from, to, label(&symbols)));
+    }
+    for st in &dfa.accepting {
+        out.push_str(&format!("q{} --> [*]\n", st));
+    }
+    out.push_str("@enduml\n");
+    out
+}
+
+/// For writing the automaton as a LaTeX TikZ picture using the automata library
+/// States are placed on a grid four wide starting with the start state, loops sit above their state,
+/// and edges bend when they run both ways between two states or would pass through another state
+/// - Input: The Dfa
+/// - Output: String holding the tikzpicture environment
+pub fn tikz(dfa: &Dfa) -> String {
+    //start state first, then the rest in order
+    let mut order = vec![dfa.start];
+    for st in 0..dfa.transitions.len() {
+        if st != dfa.start {
+            order.push(st);
+        }
+    }
+    let position = |st: usize| order.iter().position(|x| *x == st).unwrap();
+
+    let mut out = String::new();
+    out.push_str("% needs \\usepackage{tikz} and \\usetikzlibrary{automata, positioning, arrows.meta}\n");
+    out.push_str("\\begin{tikzpicture}[->, >=Stealth, node distance=2.5cm, auto, initial text=]\n");
+    for (i, st) in order.iter().enumerate() {
+        let mut style = String::from("state");
+        if *st == dfa.start {
+            style.push_str(", initial");
+        }
+        if dfa.accepting.contains(st) {
+            style.push_str(", accepting");
+        }
+        if i % 4 != 0 {
+            style.push_str(&format!(", right=of q{}", order[i - 1]));
+        }
+        else if i > 0 {
+            style.push_str(&format!(", below=of q{}", order[i - 4]));
+        }
+        out.push_str(&format!("    \\node[{}] (q{}) {{$q_{{{}}}$}};\n", style, st, st));
+    }
+
+    let edges = edges(dfa);
+    out.push_str("    \\path");
+    for (from, to, symbols) in &edges {
+        let pieces: Vec<String> = symbol_ranges(symbols).iter().map(|p| if p == " " {"\\textvisiblespace".to_string()} else {p.clone()}).collect();
+        let (a, b) = (position(*from), position(*to));
+        let shape = if from == to {
+            "[loop above]"
+        }
+        else if edges.iter().any(|(f, t, _)| f == to && t == from) || a / 4 == b / 4 && (a as i64 - b as i64).abs() > 1 || a % 4 == b % 4 && (a as i64 - b as i64).abs() > 4 {
+            "[bend left]"
+        }
+        else {
+            ""
+        };
+        out.push_str(&format!("\n        (q{}) edge{} node {{{}}} (q{})", from, shape, pieces.join(", "), to));
+    }
+    out.push_str(";\n\\end{tikzpicture}\n");
+    out
+}
+
+#[test]
+fn test_symbol_ranges(){
+    let letters: Vec<char> = SIGMA[..26].to_vec();
+    assert_eq!(symbol_ranges(&letters), vec!["a-z"]);
+    assert_eq!(symbol_ranges(&['a', 'b', 'd']), vec!["a", "b", "d"]);
+    assert_eq!(symbol_ranges(&['x', 'y', 'z', '0', '1', '2', ' ']), vec!["x-z", "0-2", " "]);
+}
+
+#[test]
+fn test_mermaid(){
+    let dfa = Dfa {
+        pattern: "\\d+".to_string(),
+        alphabet: SIGMA.to_vec(),
+        transitions: vec![
+            SIGMA.iter().map(|c| if c.is_ascii_digit() {Some(1)} else {None}).collect(),
+            SIGMA.iter().map(|c| if c.is_ascii_digit() {Some(1)} else {None}).collect(),
+        ],
+        start: 0,
+        accepting: vec![1],
+    };
+    assert_eq!(mermaid(&dfa), "stateDiagram-v2\n    [*] --> q0\n    q0 --> q1: 0-9\n    q1 --> q1: 0-9\n    q1 --> [*]\n");
+}
+
+#[test]
+fn test_tikz(){
+    //a*b with a space allowed as well as the b
+    let mut start = vec![None; SIGMA.len()];
+    start[0] = Some(0);
+    start[1] = Some(1);
+    start[36] = Some(1);
+    let dfa = Dfa {
+        pattern: String::new(),
+        alphabet: SIGMA.to_vec(),
+        transitions: vec![start, vec![None; SIGMA.len()]],
+        start: 0,
+        accepting: vec![1],
+    };
+    let picture = tikz(&dfa);
+    assert!(picture.contains("\\node[state, initial] (q0) {$q_{0}$};"));
+    assert!(picture.contains("\\node[state, accepting, right=of q0] (q1) {$q_{1}$};"));
+    assert!(picture.contains("(q0) edge[loop above] node {a} (q0)"));
+    assert!(picture.contains("(q0) edge node {b, \\textvisiblespace} (q1);"));
+}
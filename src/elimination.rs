@@ -0,0 +1,92 @@
+//! # Automaton to regex
+//! Turns a Dfa back into a Regex tree by state elimination. A new start state moves to the old one on ε
+//! and every accepting state moves to a new end state on ε, then the other states are removed one at a time,
+//! each move through a removed state k becoming a move labelled (in)(loop on k)*(out).
+//! When only the new start and end are left, the label between them is the regex.
+//! Labels are made with the constructors in simplify.rs so they stay readable as they grow.
+
+use crate::dfa::Dfa;
+use crate::regex::Regex;
+use crate::simplify::{concat, star, union};
+
+/// For building a regex that accepts the same strings as a Dfa
+/// - Input: The Dfa
+/// - Output: The Regex tree (Regex::Empty if nothing is accepted)
+pub fn dfa_to_regex(dfa: &Dfa) -> Regex {
+    //only states on the way from the start to acceptance matter
+    let live = dfa.live_states();
+    let mut useful = vec![false; dfa.transitions.len()];
+    let mut stack = vec![dfa.start];
+    while let Some(state) = stack.pop() {
+        if live[state] && !useful[state] {
+            useful[state] = true;
+            stack.extend(dfa.transitions[state].iter().flatten());
+        }
+    }
+    if !useful[dfa.start] {
+        return Regex::Empty;
+    }
+
+    //label[i][j] is the regex for moving from i to j, the new start is n and the new end is n + 1
+    let n = dfa.transitions.len();
+    let mut label: Vec<Vec<Option<Regex>>> = vec![vec![None; n + 2]; n + 2];
+    label[n][dfa.start] = Some(Regex::Epsilon);
+    for (from, row) in dfa.transitions.iter().enumerate() {
+        if !useful[from] {
+            continue;
+        }
+        for (i, target) in row.iter().enumerate() {
+            if let Some(to) = target.filter(|to| useful[*to]) {
+                let symbols = label[from][to].get_or_insert(Regex::Symbols(Vec::new()));
+                if let Regex::Symbols(list) = symbols {
+                    list.push(dfa.alphabet[i]);
+                }
+            }
+        }
+        if dfa.is_accepting(from) {
+            label[from][n + 1] = Some(Regex::Epsilon);
+        }
+    }
+
+    //remove the state with the fewest paths through it first, which keeps the labels short
+    let mut remaining: Vec<usize> = (0..n).filter(|st| useful[*st]).collect();
+    while !remaining.is_empty() {
+        let paths = |k: usize| {
+            let ins = (0..n + 2).filter(|i| *i != k && label[*i][k].is_some()).count();
+            let outs = (0..n + 2).filter(|j| *j != k && label[k][*j].is_some()).count();
+            ins * outs
+        };
+        let at = (0..remaining.len()).min_by_key(|i| paths(remaining[*i])).unwrap();
+        let k = remaining.remove(at);
+
+        let repeat = label[k][k].take().map(star).unwrap_or(Regex::Epsilon);
+        let outs: Vec<(usize, Regex)> = (0..n + 2).filter_map(|j| label[k][j].take().map(|out| (j, out))).collect();
+        for row in label.iter_mut() {
+            let into = match row[k].take() {
+                Some(into) => into,
+                None => continue,
+            };
+            for (j, out) in &outs {
+                let through = concat(vec![into.clone(), repeat.clone(), out.clone()]);
+                row[*j] = Some(match row[*j].take() {
+                    Some(direct) => union(vec![direct, through]),
+                    None => through,
+                });
+            }
+        }
+    }
+    label[n][n + 1].take().unwrap_or(Regex::Empty)
+}
+
+#[test]
+fn test_dfa_to_regex(){
+    use crate::build;
+    use crate::regex::to_pattern;
+
+    assert_eq!(to_pattern(&dfa_to_regex(&build("a*b"))), "a*b");
+    assert_eq!(to_pattern(&dfa_to_regex(&build("\\d+&\\d"))), "\\d");
+    assert_eq!(dfa_to_regex(&build("a&b")), Regex::Empty);
+    //a copy next to a + is not merged into it
+    assert_eq!(to_pattern(&dfa_to_regex(&build("aa+"))), "aa+");
+    assert_eq!(to_pattern(&dfa_to_regex(&build("a+a+"))), "aa+");
+}
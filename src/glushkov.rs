@@ -0,0 +1,134 @@
+//! # Glushkov's construction (the position automaton)
+//! Numbers every symbol in the regex by where it appears, so (ab|b)*a has positions a1 b2 b3 a4, then works out
+//!     first: positions that can start a match      last: positions that can end one
+//!     follow(p): positions that can come straight after p
+//! State 0 is the start and state p means position p was just read, so the Nfa has no epsilon moves
+//! and exactly n + 1 states for n positions. Reading position q's symbol moves to q from 0 if q is in first,
+//! or from p if q is in follow(p). The accepting states are the last positions, and 0 if the regex matches ε.
+//! '&' and '~' have no positions of their own, so regexes using them can't be built this way.
+
+use crate::nfa::Nfa;
+use crate::regex::Regex;
+
+/// What the construction needs to know about part of the tree
+/// - nullable: Whether the part matches ε
+/// - first: Positions that can start a match of the part
+/// - last: Positions that can end a match of the part
+struct Part {
+    nullable: bool,
+    first: Vec<usize>,
+    last: Vec<usize>,
+}
+
+/// The positions found so far
+/// - symbols: For every position (from 1), the symbols it matches (index 0 is the start and unused)
+/// - follow: For every position, the positions that can come after it
+struct Positions {
+    symbols: Vec<Vec<char>>,
+    follow: Vec<Vec<usize>>,
+}
+
+/// For building the position automaton of a tree
+/// - Input: The tree
+/// - Output: The epsilon free Nfa, or a message if the tree uses '&' or '~'
+pub fn glushkov(regex: &Regex) -> Result<Nfa, String> {
+    let mut positions = Positions { symbols: vec![Vec::new()], follow: vec![Vec::new()] };
+    let whole = walk(regex, &mut positions)?;
+
+    let mut transitions = Vec::new();
+    for (from, targets) in std::iter::once(&whole.first).chain(positions.follow.iter().skip(1)).enumerate() {
+        for to in targets {
+            for c in &positions.symbols[*to] {
+                transitions.push((from, Some(*c), *to));
+            }
+        }
+    }
+    let mut accepting = whole.last;
+    if whole.nullable {
+        accepting.push(0);
+    }
+    accepting.sort_unstable();
+    accepting.dedup();
+    Ok(Nfa { states: positions.symbols.len(), start: 0, accepting, transitions })
+}
+
+/// For adding one set of positions to another without repeats
+fn add(to: &mut Vec<usize>, from: &[usize]) {
+    for p in from {
+        if !to.contains(p) {
+            to.push(*p);
+        }
+    }
+}
+
+/// For numbering the positions in part of the tree and working out its first, last, and follow sets
+/// - Input: The part of the tree, and the positions so far
+/// - Output: The part's nullable, first, and last
+fn walk(regex: &Regex, positions: &mut Positions) -> Result<Part, String> {
+    match regex {
+        Regex::Symbols(symbols) => {
+            positions.symbols.push(symbols.clone());
+            positions.follow.push(Vec::new());
+            let p = positions.symbols.len() - 1;
+            Ok(Part { nullable: false, first: vec![p], last: vec![p] })
+        }
+        Regex::Epsilon => Ok(Part { nullable: true, first: Vec::new(), last: Vec::new() }),
+        Regex::Empty => Ok(Part { nullable: false, first: Vec::new(), last: Vec::new() }),
+        Regex::Concat(parts) => {
+            let mut whole = Part { nullable: true, first: Vec::new(), last: Vec::new() };
+            for part in parts {
+                let next = walk(part, positions)?;
+                //anything that can end what came before can be followed by anything that can start this part
+                for p in &whole.last {
+                    add(&mut positions.follow[*p], &next.first);
+                }
+                if whole.nullable {
+                    add(&mut whole.first, &next.first);
+                }
+                if !next.nullable {
+                    whole.last.clear();
+                }
+                add(&mut whole.last, &next.last);
+                whole.nullable = whole.nullable && next.nullable;
+            }
+            Ok(whole)
+        }
+        Regex::Union(options) => {
+            let mut whole = Part { nullable: false, first: Vec::new(), last: Vec::new() };
+            for option in options {
+                let next = walk(option, positions)?;
+                whole.nullable = whole.nullable || next.nullable;
+                add(&mut whole.first, &next.first);
+                add(&mut whole.last, &next.last);
+            }
+            Ok(whole)
+        }
+        Regex::Star(inner) | Regex::Plus(inner) => {
+            let mut part = walk(inner, positions)?;
+            //a repeat can start over after any of its ends
+            for p in &part.last {
+                let first = part.first.clone();
+                add(&mut positions.follow[*p], &first);
+            }
+            if let Regex::Star(_) = regex {
+                part.nullable = true;
+            }
+            Ok(part)
+        }
+        Regex::And(_, _) | Regex::Minus(_, _) => Err("the glushkov construction can't build '&' or '~'".to_string()),
+    }
+}
+
+#[test]
+fn test_glushkov(){
+    use crate::tree;
+
+    //(ab|b)*a has 4 positions, so 5 states and no epsilon moves
+    let nfa = glushkov(&tree("(ab|b)*a")).unwrap();
+    assert_eq!(nfa.states, 5);
+    assert!(nfa.transitions.iter().all(|(_, symbol, _)| symbol.is_some()));
+    assert_eq!(nfa.accepting, vec![4]);
+    assert!(nfa.transitions.contains(&(0, Some('a'), 1)) && nfa.transitions.contains(&(2, Some('b'), 3)));
+    assert_eq!(glushkov(&tree("a*")).unwrap().accepting, vec![0, 1]);
+    assert!(glushkov(&tree("a&b")).is_err());
+}
@@ -0,0 +1,373 @@
+//! # JFLAP .jff export and import
+//! JFLAP saves finite automata as XML:
+//! ```text
+//! <structure>
+//!   <type>fa</type>
+//!   <automaton>
+//!     <state id="0" name="q0"><x>100.0</x><y>100.0</y><initial/></state>
+//!     <state id="1" name="q1"><x>250.0</x><y>100.0</y><final/></state>
+//!     <transition><from>0</from><to>1</to><read>a</read></transition>
+//!   </automaton>
+//! </structure>
+//! ```
+//! An empty <read/> is an epsilon (lambda) move, and a read of several characters reads them in order.
+//! No external crates are used, so this file holds a small XML reader as well.
+
+use crate::nfa::Nfa;
+use crate::SIGMA;
+
+/// A parsed XML element, only what a .jff file needs
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+}
+
+impl Element {
+    /// For finding the first child with a given tag name
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    /// For finding the value of an attribute
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// For writing an automaton as a JFLAP .jff file
+/// States are laid out on a grid six states wide
+/// - Input: The automaton
+/// - Output: String holding the XML document
+pub fn nfa_to_jff(nfa: &Nfa) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n");
+    out.push_str("<structure>\n\t<type>fa</type>\n\t<automaton>\n");
+    out.push_str("\t\t<!--The list of states.-->\n");
+    for st in 0..nfa.states {
+        out.push_str(&format!("\t\t<state id=\"{}\" name=\"q{}\">\n", st, st));
+        out.push_str(&format!("\t\t\t<x>{}.0</x>\n", 100 + 150 * (st % 6)));
+        out.push_str(&format!("\t\t\t<y>{}.0</y>\n", 100 + 150 * (st / 6)));
+        if st == nfa.start {
+            out.push_str("\t\t\t<initial/>\n");
+        }
+        if nfa.accepting.contains(&st) {
+            out.push_str("\t\t\t<final/>\n");
+        }
+        out.push_str("\t\t</state>\n");
+    }
+    out.push_str("\t\t<!--The list of transitions.-->\n");
+    for (from, symbol, to) in &nfa.transitions {
+        out.push_str("\t\t<transition>\n");
+        out.push_str(&format!("\t\t\t<from>{}</from>\n", from));
+        out.push_str(&format!("\t\t\t<to>{}</to>\n", to));
+        match symbol {
+            Some(c) => out.push_str(&format!("\t\t\t<read>{}</read>\n", c)),
+            None => out.push_str("\t\t\t<read/>\n"),
+        }
+        out.push_str("\t\t</transition>\n");
+    }
+    out.push_str("\t</automaton>\n</structure>\n");
+    out
+}
+
+/// For reading an automaton from a JFLAP .jff file
+/// State ids are renumbered 0..n in the order the states are listed
+/// - Input: String holding the XML document
+/// - Output: The automaton, or a message saying what is wrong with the document
+pub fn nfa_from_jff(text: &str) -> Result<Nfa, String> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+    let root = parser.parse_document()?;
+    if root.name != "structure" {
+        return Err("not a JFLAP structure".to_string());
+    }
+    match root.child("type") {
+        Some(t) if t.text.trim() == "fa" => {}
+        _ => return Err("only finite automata (type fa) are supported".to_string()),
+    }
+    let automaton = root.child("automaton").ok_or("missing <automaton>")?;
+
+    //states, map the ids used in the file to 0..n
+    let mut ids = Vec::new();
+    let mut start = None;
+    let mut accepting = Vec::new();
+    for state in automaton.children.iter().filter(|c| c.name == "state") {
+        let id = state.attribute("id").ok_or("state without an id")?.to_string();
+        if ids.contains(&id) {
+            return Err(format!("state {} is listed twice", id));
+        }
+        ids.push(id);
+        if state.child("initial").is_some() {
+            if start.is_some() {
+                return Err("more than one initial state".to_string());
+            }
+            start = Some(ids.len() - 1);
+        }
+        if state.child("final").is_some() {
+            accepting.push(ids.len() - 1);
+        }
+    }
+    let start = start.ok_or("no initial state")?;
+
+    let mut nfa = Nfa { states: ids.len(), start, accepting, transitions: Vec::new() };
+    for transition in automaton.children.iter().filter(|c| c.name == "transition") {
+        let state_of = |tag: &str| -> Result<usize, String> {
+            let id = transition.child(tag).ok_or(format!("transition without <{}>", tag))?.text.trim().to_string();
+            ids.iter().position(|x| *x == id).ok_or(format!("unknown state {}", id))
+        };
+        let from = state_of("from")?;
+        let to = state_of("to")?;
+        let read: Vec<char> = match transition.child("read") {
+            Some(read) => read.text.chars().collect(),
+            None => Vec::new(),
+        };
+        for c in &read {
+            if !SIGMA.contains(c) {
+                return Err(format!("symbol '{}' is not in the alphabet", c));
+            }
+        }
+
+        if read.len() <= 1 {
+            nfa.transitions.push((from, read.first().copied(), to));
+        }
+        else {
+            //reading a whole string, add an unnamed state between each character
+            let mut current = from;
+            for (i, c) in read.iter().enumerate() {
+                let next = if i == read.len() - 1 {to} else {nfa.states};
+                if next == nfa.states {
+                    nfa.states += 1;
+                }
+                nfa.transitions.push((current, Some(*c), next));
+                current = next;
+            }
+        }
+    }
+    Ok(nfa)
+}
+
+/// A recursive descent reader for the parts of XML a .jff file uses
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn starts_with(&self, s: &str) -> bool {
+        s.chars().enumerate().all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    /// For moving past the next occurrence of a string
+    fn skip_past(&mut self, s: &str) -> Result<(), String> {
+        while self.pos < self.chars.len() {
+            if self.starts_with(s) {
+                self.pos += s.chars().count();
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+        Err(format!("missing \"{}\"", s))
+    }
+
+    /// For moving past whitespace, comments, and <?...?> / <!...> declarations
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+                self.pos += 1;
+            }
+            if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            }
+            else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            }
+            else if self.starts_with("<!") {
+                self.skip_past(">")?;
+            }
+            else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// For reading the root element, nothing but comments may follow it
+    fn parse_document(&mut self) -> Result<Element, String> {
+        self.skip_misc()?;
+        let root = self.parse_element()?;
+        self.skip_misc()?;
+        if self.pos < self.chars.len() {
+            return Err("unexpected text after the root element".to_string());
+        }
+        Ok(root)
+    }
+
+    fn parse_name(&mut self) -> String {
+        let begin = self.pos;
+        while self.pos < self.chars.len() && (self.chars[self.pos].is_alphanumeric() || "_-.:".contains(self.chars[self.pos])) {
+            self.pos += 1;
+        }
+        self.chars[begin..self.pos].iter().collect()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn parse_element(&mut self) -> Result<Element, String> {
+        if !self.starts_with("<") {
+            return Err(format!("expected '<' at character {}", self.pos));
+        }
+        self.pos += 1;
+        let name = self.parse_name();
+        if name.is_empty() {
+            return Err(format!("expected a tag name at character {}", self.pos));
+        }
+        let mut element = Element { name, attributes: Vec::new(), children: Vec::new(), text: String::new() };
+
+        //attributes
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("/>") {
+                self.pos += 2;
+                return Ok(element);
+            }
+            if self.starts_with(">") {
+                self.pos += 1;
+                break;
+            }
+            let key = self.parse_name();
+            self.skip_whitespace();
+            if key.is_empty() || !self.starts_with("=") {
+                return Err(format!("invalid attribute in <{}>", element.name));
+            }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = *self.chars.get(self.pos).ok_or("unexpected end of document")?;
+            if quote != '"' && quote != '\'' {
+                return Err(format!("unquoted attribute in <{}>", element.name));
+            }
+            self.pos += 1;
+            let begin = self.pos;
+            self.skip_past(&quote.to_string())?;
+            let value: String = self.chars[begin..self.pos - 1].iter().collect();
+            element.attributes.push((key, unescape(&value)));
+        }
+
+        //contents, up to the matching close tag
+        let mut text = String::new();
+        loop {
+            if self.pos >= self.chars.len() {
+                return Err(format!("<{}> is never closed", element.name));
+            }
+            if self.starts_with("</") {
+                self.pos += 2;
+                let close = self.parse_name();
+                if close != element.name {
+                    return Err(format!("<{}> closed by </{}>", element.name, close));
+                }
+                self.skip_whitespace();
+                if !self.starts_with(">") {
+                    return Err(format!("invalid close tag </{}>", close));
+                }
+                self.pos += 1;
+                element.text = unescape(&text);
+                return Ok(element);
+            }
+            if self.starts_with("<!--") || self.starts_with("<?") {
+                self.skip_misc()?;
+            }
+            else if self.starts_with("<") {
+                element.children.push(self.parse_element()?);
+            }
+            else {
+                text.push(self.chars[self.pos]);
+                self.pos += 1;
+            }
+        }
+    }
+}
+
+/// For replacing XML entities (&lt; &#13; &#x41; etc.) with the characters they stand for
+/// Unknown entities are left as they are
+fn unescape(s: &str) -> String {
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let entity = &rest[1..end];
+        let replacement = match entity {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ if entity.starts_with("#x") => u32::from_str_radix(&entity[2..], 16).ok().and_then(std::char::from_u32),
+            _ if entity.starts_with('#') => entity[1..].parse::<u32>().ok().and_then(std::char::from_u32),
+            _ => None,
+        };
+        match replacement {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[test]
+fn test_jff_round_trip(){
+    let nfa = Nfa {
+        states: 3,
+        start: 0,
+        accepting: vec![2],
+        transitions: vec![(0, Some('a'), 1), (1, None, 2), (2, Some(' '), 0)],
+    };
+    assert_eq!(nfa_from_jff(&nfa_to_jff(&nfa)), Ok(nfa));
+}
+
+#[test]
+fn test_nfa_from_jff(){
+    //the way JFLAP itself saves a file, with a multi character read
+    let text = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?><!--Created with JFLAP 7.1.--><structure>&#13;
+	<type>fa</type>&#13;
+	<automaton>&#13;
+		<!--The list of states.-->&#13;
+		<state id=\"4\" name=\"q4\">&#13;
+			<x>54.0</x>&#13;
+			<y>119.0</y>&#13;
+			<initial/>&#13;
+		</state>&#13;
+		<state id=\"9\" name=\"q9\">&#13;
+			<x>200.0</x>&#13;
+			<y>119.0</y>&#13;
+			<final/>&#13;
+		</state>&#13;
+		<transition>&#13;
+			<from>4</from>&#13;
+			<to>9</to>&#13;
+			<read>ab</read>&#13;
+		</transition>&#13;
+	</automaton>&#13;
+</structure>";
+    let nfa = nfa_from_jff(text).unwrap();
+    assert_eq!(nfa.states, 3);
+    assert_eq!(nfa.accepting, vec![1]);
+    assert_eq!(nfa.transitions, vec![(0, Some('a'), 2), (2, Some('b'), 1)]);
+
+    assert!(nfa_from_jff(&text.replace("<type>fa</type>", "<type>turing</type>")).is_err());
+}
@@ -0,0 +1,388 @@
+//! # JSON export and import of a Dfa
+//! Schema (version 1):
+//! ```text
+//! {
+//!   "format": "awoods-dfa",           //always this string
+//!   "version": 1,                     //schema version
+//!   "pattern": "a*b",                 //regex the automaton came from, "" if unknown
+//!   "alphabet": ["a", "b", ...],      //one character strings, all must be in SIGMA
+//!   "states": [0, 1],                 //state ids, any non-negative integers
+//!   "start": 0,                       //id of the start state
+//!   "accepting": [1],                 //ids of the accepting states
+//!   "transitions": [                  //one entry per defined transition, missing ones reject
+//!     {"from": 0, "symbol": "a", "to": 0},
+//!     {"from": 0, "symbol": "b", "to": 1}
+//!   ]
+//! }
+//! ```
+//! No external crates are used, so this file holds a small JSON reader and writer as well.
+
+use crate::dfa::Dfa;
+use crate::SIGMA;
+
+/// A parsed JSON value
+#[derive(Debug, Clone, PartialEq)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// For writing a Dfa as JSON following the schema above
+/// - Input: The Dfa
+/// - Output: String holding the JSON document
+pub fn dfa_to_json(dfa: &Dfa) -> String {
+    let mut out = String::new();
+    out.push_str("{\n");
+    out.push_str("  \"format\": \"awoods-dfa\",\n");
+    out.push_str("  \"version\": 1,\n");
+    out.push_str(&format!("  \"pattern\": {},\n", quote(&dfa.pattern)));
+
+    let alphabet: Vec<String> = dfa.alphabet.iter().map(|c| quote(&c.to_string())).collect();
+    out.push_str(&format!("  \"alphabet\": [{}],\n", alphabet.join(", ")));
+    let states: Vec<String> = (0..dfa.transitions.len()).map(|s| s.to_string()).collect();
+    out.push_str(&format!("  \"states\": [{}],\n", states.join(", ")));
+    out.push_str(&format!("  \"start\": {},\n", dfa.start));
+    let accepting: Vec<String> = dfa.accepting.iter().map(|s| s.to_string()).collect();
+    out.push_str(&format!("  \"accepting\": [{}],\n", accepting.join(", ")));
+
+    //one transition per line so two automata diff nicely
+    let mut transitions = Vec::new();
+    for (from, row) in dfa.transitions.iter().enumerate() {
+        for (i, target) in row.iter().enumerate() {
+            if let Some(to) = target {
+                transitions.push(format!("    {{\"from\": {}, \"symbol\": {}, \"to\": {}}}", from, quote(&dfa.alphabet[i].to_string()), to));
+            }
+        }
+    }
+    if transitions.is_empty() {
+        out.push_str("  \"transitions\": []\n");
+    }
+    else {
+        out.push_str("  \"transitions\": [\n");
+        out.push_str(&transitions.join(",\n"));
+        out.push_str("\n  ]\n");
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// For reading a Dfa back from JSON following the schema above
+/// State ids are renumbered 0..n in the order they are listed
+/// - Input: String holding the JSON document
+/// - Output: The Dfa, or a message saying what is wrong with the document
+pub fn dfa_from_json(text: &str) -> Result<Dfa, String> {
+    let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+    let doc = parser.parse_document()?;
+
+    if get(&doc, "format")? != &Json::Str("awoods-dfa".to_string()) {
+        return Err("not an awoods-dfa document".to_string());
+    }
+    if as_index(get(&doc, "version")?)? != 1 {
+        return Err("unsupported version".to_string());
+    }
+    let pattern = match get(&doc, "pattern")? {
+        Json::Str(s) => s.clone(),
+        _ => return Err("\"pattern\" must be a string".to_string()),
+    };
+
+    //alphabet, every symbol has to be usable by check_string
+    let mut alphabet = Vec::new();
+    for sym in as_array(get(&doc, "alphabet")?)? {
+        let sym = as_symbol(sym)?;
+        if !SIGMA.contains(&sym) {
+            return Err(format!("symbol '{}' is not in the alphabet", sym));
+        }
+        if alphabet.contains(&sym) {
+            return Err(format!("symbol '{}' is listed twice", sym));
+        }
+        alphabet.push(sym);
+    }
+
+    //states, map the ids used in the file to 0..n
+    let mut ids = Vec::new();
+    for st in as_array(get(&doc, "states")?)? {
+        let id = as_index(st)?;
+        if ids.contains(&id) {
+            return Err(format!("state {} is listed twice", id));
+        }
+        ids.push(id);
+    }
+    let state_of = |v: &Json| -> Result<usize, String> {
+        let id = as_index(v)?;
+        ids.iter().position(|&x| x == id).ok_or(format!("unknown state {}", id))
+    };
+
+    let start = state_of(get(&doc, "start")?)?;
+    let mut accepting = Vec::new();
+    for st in as_array(get(&doc, "accepting")?)? {
+        let st = state_of(st)?;
+        if !accepting.contains(&st) {
+            accepting.push(st);
+        }
+    }
+    accepting.sort_unstable();
+
+    let mut transitions = vec![vec![None; alphabet.len()]; ids.len()];
+    for tr in as_array(get(&doc, "transitions")?)? {
+        let from = state_of(get(tr, "from")?)?;
+        let to = state_of(get(tr, "to")?)?;
+        let sym = as_symbol(get(tr, "symbol")?)?;
+        let i = alphabet.iter().position(|&x| x == sym).ok_or(format!("symbol '{}' is not in \"alphabet\"", sym))?;
+        match transitions[from][i] {
+            Some(other) if other != to => return Err(format!("state {} has two transitions on '{}'", ids[from], sym)),
+            _ => transitions[from][i] = Some(to),
+        }
+    }
+
+    Ok(Dfa { pattern, alphabet, transitions, start, accepting })
+}
+
+/// For quoting and escaping a string for JSON output
+/// - Input: The string
+/// - Output: The quoted string
+fn quote(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// For looking up a key in a JSON object
+/// - Input: The object and key
+/// - Output: The value, or an error if it is missing
+fn get<'a>(value: &'a Json, key: &str) -> Result<&'a Json, String> {
+    if let Json::Object(fields) = value {
+        for (k, v) in fields {
+            if k == key {
+                return Ok(v);
+            }
+        }
+    }
+    Err(format!("missing \"{}\"", key))
+}
+
+/// For reading a JSON value as an array
+fn as_array(value: &Json) -> Result<&Vec<Json>, String> {
+    match value {
+        Json::Array(items) => Ok(items),
+        _ => Err("expected an array".to_string()),
+    }
+}
+
+/// For reading a JSON value as a non-negative whole number
+fn as_index(value: &Json) -> Result<usize, String> {
+    match value {
+        Json::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Ok(*n as usize),
+        _ => Err("expected a non-negative integer".to_string()),
+    }
+}
+
+/// For reading a JSON value as a single character string
+fn as_symbol(value: &Json) -> Result<char, String> {
+    if let Json::Str(s) = value {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(c);
+        }
+    }
+    Err("expected a one character string".to_string())
+}
+
+/// A recursive descent reader for JSON text
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    /// For reading a whole document, nothing but whitespace may follow the value
+    fn parse_document(&mut self) -> Result<Json, String> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos < self.chars.len() {
+            return Err(format!("unexpected '{}' after the document", self.chars[self.pos]));
+        }
+        Ok(value)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    /// For checking the next character is the one expected and moving past it
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.pos < self.chars.len() && self.chars[self.pos] == c {
+            self.pos += 1;
+            Ok(())
+        }
+        else {
+            Err(format!("expected '{}' at character {}", c, self.pos))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.get(self.pos) {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(Json::Str(self.parse_string()?)),
+            Some('t') => self.parse_word("true", Json::Bool(true)),
+            Some('f') => self.parse_word("false", Json::Bool(false)),
+            Some('n') => self.parse_word("null", Json::Null),
+            Some(c) if *c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) => Err(format!("unexpected '{}' at character {}", c, self.pos)),
+            None => Err("unexpected end of document".to_string()),
+        }
+    }
+
+    fn parse_word(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        for c in word.chars() {
+            if self.chars.get(self.pos) != Some(&c) {
+                return Err(format!("invalid literal at character {}", self.pos));
+            }
+            self.pos += 1;
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<Json, String> {
+        let begin = self.pos;
+        while self.pos < self.chars.len() && (self.chars[self.pos].is_ascii_digit() || "+-.eE".contains(self.chars[self.pos])) {
+            self.pos += 1;
+        }
+        let text: String = self.chars[begin..self.pos].iter().collect();
+        text.parse::<f64>().map(Json::Number).map_err(|_| format!("invalid number \"{}\"", text))
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        loop {
+            let c = *self.chars.get(self.pos).ok_or("unterminated string")?;
+            self.pos += 1;
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let esc = *self.chars.get(self.pos).ok_or("unterminated string")?;
+                    self.pos += 1;
+                    match esc {
+                        '"' => out.push('"'),
+                        '\\' => out.push('\\'),
+                        '/' => out.push('/'),
+                        'b' => out.push('\u{8}'),
+                        'f' => out.push('\u{c}'),
+                        'n' => out.push('\n'),
+                        'r' => out.push('\r'),
+                        't' => out.push('\t'),
+                        'u' => {
+                            if self.pos + 4 > self.chars.len() {
+                                return Err("unterminated string".to_string());
+                            }
+                            let hex: String = self.chars[self.pos..self.pos + 4].iter().collect();
+                            self.pos += 4;
+                            let code = u32::from_str_radix(&hex, 16).map_err(|_| format!("invalid escape \\u{}", hex))?;
+                            out.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                        }
+                        _ => return Err(format!("invalid escape \\{}", esc)),
+                    }
+                }
+                _ => out.push(c),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.pos) == Some(&']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.chars.get(self.pos) {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(format!("expected ',' or ']' at character {}", self.pos)),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, String> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.chars.get(self.pos) == Some(&'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            fields.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.chars.get(self.pos) {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(format!("expected ',' or '}}' at character {}", self.pos)),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_json_round_trip(){
+    let dfa = Dfa {
+        pattern: "a*\\d".to_string(),
+        alphabet: vec!['a', '1'],
+        transitions: vec![vec![Some(0), Some(1)], vec![None, None]],
+        start: 0,
+        accepting: vec![1],
+    };
+    assert_eq!(dfa_from_json(&dfa_to_json(&dfa)), Ok(dfa));
+}
+
+#[test]
+fn test_dfa_from_json(){
+    //state ids don't need to start at 0
+    let text = r#"{"format": "awoods-dfa", "version": 1, "pattern": "", "alphabet": ["x"],
+        "states": [7, 3], "start": 3, "accepting": [7],
+        "transitions": [{"from": 3, "symbol": "x", "to": 7}]}"#;
+    let dfa = dfa_from_json(text).unwrap();
+    assert_eq!(dfa.start, 1);
+    assert_eq!(dfa.accepting, vec![0]);
+    assert_eq!(dfa.transitions, vec![vec![None], vec![Some(0)]]);
+
+    //symbols outside of SIGMA can't be checked
+    let bad = text.replace("[\"x\"]", "[\"X\"]");
+    assert!(dfa_from_json(&bad).is_err());
+    //a state can't go two places on one symbol
+    let bad = text.replace("\"to\": 7}]", "\"to\": 7}, {\"from\": 3, \"symbol\": \"x\", \"to\": 3}]");
+    assert!(dfa_from_json(&bad).is_err());
+}
@@ -0,0 +1,146 @@
+//! # Lazy DFA
+//! Subset construction done on demand: a Dfa state (a set of Nfa states) is only made the first time some input
+//! reaches it, and each move is worked out by simulation the first time it is taken, then remembered.
+//! Long inputs mostly revisit the same few states, so matching runs at table speed without building the
+//! whole table, which can need exponentially many states. The cache holds at most a set number of states;
+//! when it is full everything is thrown away and built again as needed, so memory stays bounded.
+
+use std::collections::HashMap;
+
+use crate::simulate::Simulator;
+use crate::SIGMA;
+
+/// What is known about one move of a cached state
+/// - Unknown: Not worked out yet
+/// - Dead: No Nfa state is left, the input is rejected
+/// - To: The cached state it leads to
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Move {
+    Unknown,
+    Dead,
+    To(usize),
+}
+
+/// A Dfa built as input reaches it
+/// - simulator: The Nfa the states come from
+/// - sets: For every cached state, its set of Nfa states (state 0 is always the start)
+/// - index: The cached state for each set
+/// - moves: For every cached state, its move on each symbol of SIGMA
+/// - accepting: For every cached state, whether it accepts
+/// - capacity: Most states to cache before flushing
+/// - flushes: How many times the cache has been flushed
+pub struct LazyDfa {
+    simulator: Simulator,
+    sets: Vec<Vec<usize>>,
+    index: HashMap<Vec<usize>, usize>,
+    moves: Vec<Vec<Move>>,
+    accepting: Vec<bool>,
+    capacity: usize,
+    pub flushes: usize,
+}
+
+impl LazyDfa {
+    /// For making a lazy Dfa with an empty cache (apart from the start state)
+    /// - Input: The simulator for the Nfa, and the most states to cache (at least 2 are kept)
+    /// - Output: The LazyDfa
+    pub fn new(simulator: Simulator, capacity: usize) -> LazyDfa {
+        let mut lazy = LazyDfa {
+            simulator,
+            sets: Vec::new(),
+            index: HashMap::new(),
+            moves: Vec::new(),
+            accepting: Vec::new(),
+            capacity: capacity.max(2),
+            flushes: 0,
+        };
+        lazy.flush();
+        lazy.flushes = 0;
+        lazy
+    }
+
+    /// For emptying the cache, leaving only the start state
+    fn flush(&mut self) {
+        self.sets.clear();
+        self.index.clear();
+        self.moves.clear();
+        self.accepting.clear();
+        let start = self.simulator.start();
+        self.add(start);
+        self.flushes += 1;
+    }
+
+    /// For caching a new state
+    fn add(&mut self, set: Vec<usize>) -> usize {
+        self.accepting.push(self.simulator.is_accepting(&set));
+        self.moves.push(vec![Move::Unknown; SIGMA.len()]);
+        self.index.insert(set.clone(), self.sets.len());
+        self.sets.push(set);
+        self.sets.len() - 1
+    }
+
+    /// For taking a move, working it out and caching it the first time
+    /// - Input: The cached state, and the symbol's position in SIGMA
+    /// - Output: The cached state moved to, or None if the input is rejected
+    fn step(&mut self, state: usize, symbol: usize) -> Option<usize> {
+        match self.moves[state][symbol] {
+            Move::To(next) => return Some(next),
+            Move::Dead => return None,
+            Move::Unknown => {}
+        }
+        let set = self.simulator.step(&self.sets[state], SIGMA[symbol]);
+        if set.is_empty() {
+            self.moves[state][symbol] = Move::Dead;
+            return None;
+        }
+        if let Some(next) = self.index.get(&set) {
+            self.moves[state][symbol] = Move::To(*next);
+            return Some(*next);
+        }
+        if self.sets.len() == self.capacity {
+            //the state moved from is gone after a flush, so the move can't be remembered this time
+            self.flush();
+            return Some(self.add(set));
+        }
+        let next = self.add(set);
+        self.moves[state][symbol] = Move::To(next);
+        Some(next)
+    }
+
+    /// For checking a string, building and caching states as it goes
+    /// - Input: The string
+    /// - Output: True if the Nfa accepts it
+    pub fn accepts(&mut self, input: &str) -> bool {
+        let mut state = 0;
+        for c in input.chars() {
+            let symbol = match SIGMA.iter().position(|x| *x == c) {
+                Some(symbol) => symbol,
+                None => return false,
+            };
+            state = match self.step(state, symbol) {
+                Some(next) => next,
+                None => return false,
+            };
+        }
+        self.accepting[state]
+    }
+}
+
+#[test]
+fn test_lazy_dfa(){
+    use crate::thompson::thompson;
+
+    //(a|b)*a(a|b)(a|b): the full Dfa has 8 states
+    let nfa = thompson(&crate::tree("(a|b)*a(a|b)(a|b)"));
+    let mut lazy = LazyDfa::new(Simulator::new(&nfa), 100);
+    assert!(lazy.accepts("abb") && lazy.accepts("bbbaba"));
+    assert!(!lazy.accepts("bbbbaa") && !lazy.accepts("ab") && !lazy.accepts("abc"));
+    assert!(lazy.sets.len() <= 8 && lazy.flushes == 0);
+
+    //a cache too small for the whole Dfa flushes but still gets the right answers
+    let mut small = LazyDfa::new(Simulator::new(&nfa), 3);
+    let full = nfa.to_dfa("");
+    for input in ["abbbaab", "aaaa", "babababa", "bbbb", ""] {
+        assert_eq!(small.accepts(input), full.accepts(input), "{}", input);
+    }
+    assert!(small.flushes > 0 && small.sets.len() <= 3);
+}
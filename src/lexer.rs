@@ -0,0 +1,137 @@
+//! # Lexer
+//! Splits lines into tokens using rules read from a file, one rule per line written as NAME = regex
+//! (blank lines and lines starting with # are skipped). Everything after the space following the '=' is the regex,
+//! trailing spaces included since a space is a symbol, but a \r left by Windows line endings is dropped.
+//! All the rules are combined into one automaton with RegexSet, then at each point in the line the longest
+//! match of any rule is taken as the next token.
+//! When two rules match the same longest text, the one written first in the file wins, like flex.
+
+use crate::dfa::Dfa;
+use crate::regex_set::RegexSet;
+
+/// One token found in the input
+/// - rule: The index of the rule it matched, or None if no rule matches at this point (the lexeme is one character)
+/// - lexeme: The text of the token
+/// - line: Line number, from 1
+/// - column: Column of its first character, from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub rule: Option<usize>,
+    pub lexeme: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// One rule from a rules file
+/// - name: The name printed for its tokens
+/// - regex: The regex its tokens match
+/// - line: The line of the file it is on, from 1
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub name: String,
+    pub regex: String,
+    pub line: usize,
+}
+
+/// For reading a rules file
+/// - Input: The contents of the file
+/// - Output: The rules in order, or a message naming the line (and rule, if it has a name) of the first line that isn't a rule
+pub fn parse_rules(text: &str) -> Result<Vec<Rule>, String> {
+    let mut rules = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, regex) = line.split_once('=').ok_or(format!("line {}: there is no '='", i + 1))?;
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("line {} ({}): the name can only have letters, digits, and _", i + 1, name));
+        }
+        //the regex can have spaces in it, so only the one after the '=' is dropped
+        let regex = regex.strip_prefix(' ').unwrap_or(regex);
+        if regex.is_empty() {
+            return Err(format!("line {} ({}): there is no regex", i + 1, name));
+        }
+        rules.push(Rule { name: name.to_string(), regex: regex.to_string(), line: i + 1 });
+    }
+    if rules.is_empty() {
+        return Err("there are no rules".to_string());
+    }
+    Ok(rules)
+}
+
+/// A lexer built from the automata for the rules
+pub struct Lexer {
+    set: RegexSet,
+}
+
+impl Lexer {
+    /// For combining the rules' automata
+    /// - Input: The automata, in priority order (first wins a tie), and the most states the combined automaton may have
+    /// - Output: The Lexer, or a message saying it needs too many states
+    pub fn new(dfas: &[Dfa], state_limit: usize) -> Result<Lexer, String> {
+        Ok(Lexer { set: RegexSet::new(dfas, state_limit)? })
+    }
+
+    /// For splitting one line into tokens, longest match first and then the earliest rule
+    /// A character where no rule matches becomes a token with no rule, and lexing carries on after it
+    /// - Input: The line, and its line number
+    /// - Output: The tokens, in order
+    pub fn tokenize(&self, text: &str, line: usize) -> Vec<Token> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut tokens = Vec::new();
+        let mut pos = 0;
+        while pos < chars.len() {
+            //run as far as the automaton goes, remembering the last place a rule accepted
+            let mut state = self.set.dfa.start;
+            let mut best: Option<(usize, usize)> = None;
+            for (i, c) in chars.iter().enumerate().skip(pos) {
+                match self.set.dfa.step(state, *c) {
+                    Some(next) => state = next,
+                    None => break,
+                }
+                if let Some(rule) = self.set.tags[state].first() {
+                    best = Some((i + 1, *rule));
+                }
+            }
+            let (end, rule) = match best {
+                Some((end, rule)) => (end, Some(rule)),
+                None => (pos + 1, None),
+            };
+            tokens.push(Token { rule, lexeme: chars[pos..end].iter().collect(), line, column: pos + 1 });
+            pos = end;
+        }
+        tokens
+    }
+}
+
+#[test]
+fn test_lexer(){
+    let rules = parse_rules("# keywords come first so they beat IDENT\nIF = if\nIDENT = \\w(\\w|\\d)*\n\nNUM = \\d+\nSPACE = ( )+\n").unwrap();
+    assert_eq!(rules[0], Rule { name: "IF".to_string(), regex: "if".to_string(), line: 2 });
+    assert_eq!(rules[3], Rule { name: "SPACE".to_string(), regex: "( )+".to_string(), line: 6 });
+    assert!(parse_rules("IF if").is_err());
+    assert_eq!(parse_rules("IF = if\nI F = if"), Err("line 2 (I F): the name can only have letters, digits, and _".to_string()));
+    assert_eq!(parse_rules("IF = if\nNUM =\n"), Err("line 2 (NUM): there is no regex".to_string()));
+    assert!(parse_rules("# nothing").is_err());
+
+    //Windows line endings don't end up in the regexes, but trailing spaces do
+    let crlf = parse_rules("IF = if\r\nSPACE =  \r\nNUM = \\d+\r").unwrap();
+    assert_eq!(crlf.iter().map(|rule| rule.regex.as_str()).collect::<Vec<&str>>(), vec!["if", " ", "\\d+"]);
+
+    let dfas: Vec<Dfa> = rules.iter().map(|rule| crate::build(&rule.regex)).collect();
+    let lexer = Lexer::new(&dfas, crate::STATE_LIMIT).unwrap();
+    let tokens = lexer.tokenize("if iffy 42.x", 3);
+    let found: Vec<(Option<usize>, &str, usize)> = tokens.iter().map(|t| (t.rule, t.lexeme.as_str(), t.column)).collect();
+    assert_eq!(found, vec![
+        (Some(0), "if", 1),
+        (Some(3), " ", 3),
+        (Some(1), "iffy", 4), //longest match beats the earlier rule
+        (Some(3), " ", 8),
+        (Some(2), "42", 9),
+        (None, ".", 11),
+        (Some(1), "x", 12),
+    ]);
+    assert!(tokens.iter().all(|t| t.line == 3));
+}
@@ -0,0 +1,256 @@
+//! # AWoods_Project1 library
+//! The automata, the regex syntax tree, and everything built on them, shared by the command line program in
+//! main.rs and the dfa! macro in dfa_macro/ so both build the same automaton for a regex.
+//! Transition tables are one row per state, one column per symbol of SIGMA (" " for no move), and a last row of
+//! "X" followed by the accepting states.
+
+pub mod dfa; //typed automaton used for saving, loading, and comparing
+pub mod json; //json export and import
+pub mod binary; //binary export and import, and the automaton cache
+pub mod nfa; //automata with epsilon or repeated moves, and subset construction
+pub mod jflap; //JFLAP .jff export and import
+pub mod diagram; //mermaid, plantuml, and tikz state diagrams
+pub mod table; //readable transition tables
+pub mod regex; //regex syntax tree
+pub mod thompson; //regex tree to nfa
+pub mod sample; //random accepted strings and near misses
+pub mod simplify; //regex tree identities
+pub mod elimination; //automaton to regex tree
+pub mod derivative; //regex tree to dfa by Brzozowski derivatives
+pub mod glushkov; //regex tree to epsilon free nfa by positions
+pub mod simulate; //matching by nfa simulation
+pub mod lazy; //dfa built as input reaches it
+pub mod spans; //where in a line the regex matches
+pub mod regex_set; //many regexes in one automaton
+pub mod lexer; //tokenizing with rules from a file
+pub mod codegen; //the automaton as source code
+
+use dfa::Dfa;
+use nfa::Nfa;
+
+//the alphabet, every automaton is over these characters and tables have a column for each in this order
+pub const SIGMA: [char; 37] = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z','0','1','2','3','4','5','6','7','8','9', ' '];
+
+//most states subset construction makes before giving up, when no other limit is given
+pub const STATE_LIMIT: usize = 10000;
+
+/// For scanning the input regex into a vector of symbols easier to parse
+/// - Input: Regex string to scan
+/// - Output: Vector containing scanned string, or a message saying what is wrong with the regex
+/// - KEY:
+///     - SIGMA -> SIGMA
+///     - \w -> !
+///     - \d -> @
+///     - (,{ -> (
+///     - ),} -> {
+///     - '*' -> *
+///     - '+' -> +
+///     - | -> |
+///     - & -> &
+///     - ~ -> ~
+pub fn scan_regex(reg: &str) -> Result<Vec<char>, String>{
+    let mut scanned = Vec::new();
+    let mut special_char = false;
+    let mut paren_count = 0; //used to make sure each ( and { has a matching ) or }
+    for (i, char) in reg.chars().enumerate(){
+        if special_char { // if the previous symbol was a '\'
+            if char == 'w' {
+                scanned.push('!');
+                special_char = false;
+            }
+            else if char == 'd' {
+                scanned.push('@');
+                special_char = false;
+            }
+            else {
+                //ERROR, not a \w or \d, not a valid regex
+                return Err(format!("'\\' at position {} must be followed by w or d", i - 1));
+            }
+        }
+        else if SIGMA.contains(&char){
+            scanned.push(char); //push any alphabet characters straight to the vec
+        }
+        else if char == '\\' {
+            special_char = true; //the next char must be a w or a d
+        }
+        else if char == '(' || char == '{' {
+            scanned.push('(');
+            paren_count += 1; //enter a paren
+        }
+        else if char == ')' || char == '}' {
+            scanned.push(')');
+            paren_count -= 1; //exit a paren
+        }
+        else if char == '*'{
+            scanned.push('*');
+        }
+        else if char == '+'{
+            scanned.push('+');
+        }
+        else if char == '|'{
+            scanned.push('|');
+        }
+        else if char == '&' || char == '~' {
+            scanned.push(char);
+        }
+        else {
+            return Err(format!("{:?} at position {} is not in the alphabet", char, i));
+        }
+    }
+    if special_char {
+        return Err("'\\' at the end must be followed by w or d".to_string());
+    }
+    if paren_count != 0 {
+        //not every parenthasis closes, error
+        return Err("brackets do not match".to_string());
+    }
+    Ok(scanned)
+}
+
+/// For parsing a regex into its syntax tree
+/// - Input: The regex
+/// - Output: The tree, or a message saying why the regex is invalid
+pub fn parse_pattern(pattern: &str) -> Result<regex::Regex, String> {
+    scan_regex(pattern).and_then(|scanned| regex::parse(&scanned))
+        .map_err(|message| format!("Invalid regex {:?}: {}", pattern, message))
+}
+
+/// For building the NFA of a regex from its syntax tree
+/// - Input: The regex, and the construction (glushkov, anything else is Thompson's)
+/// - Output: The Nfa, or a message saying why the regex is invalid
+pub fn compile_nfa(pattern: &str, construction: Option<&str>) -> Result<Nfa, String> {
+    let tree = parse_pattern(pattern)?;
+    match construction {
+        Some("glushkov") => glushkov::glushkov(&tree).map_err(|message| format!("Invalid regex {:?}: {}", pattern, message)),
+        _ => Ok(thompson::thompson(&tree)),
+    }
+}
+
+/// For building the automaton of a regex from its syntax tree
+/// The command line program, the repl, and the dfa! macro all build regexes with this, so they agree on the automaton and the errors
+/// - Input: The regex, the construction (thompson, derivative, or glushkov, None for thompson), and the most states subset construction may make
+/// - Output: The Dfa, or a message saying why the regex is invalid or that it needs too many states
+pub fn compile(pattern: &str, construction: Option<&str>, state_limit: usize) -> Result<Dfa, String> {
+    if construction == Some("derivative") {
+        return Ok(derivative::derivative_dfa(&parse_pattern(pattern)?, pattern));
+    }
+    compile_nfa(pattern, construction)?.to_dfa_limited(pattern, state_limit)
+        .ok_or(format!("Too many states: subset construction passed {} for {}", state_limit, pattern))
+}
+
+/// For navigating the transition table and seeing if strings are valid
+/// - Input: String and transition table
+/// - Output: Boolean, true if string is valid, false if not
+pub fn check_string(input: &str, table: &[Vec<String>]) -> bool{
+    let mut curr_state = 0;
+    let len = table.len()-1;
+    for char in input.chars() {
+        if curr_state > len-1 || table[curr_state][SIGMA.iter().position(|&x| x==char ).unwrap()] == " " {
+            return false //something went wrong, or there is no transition for this input from this state
+        }
+        else {
+            curr_state = table[curr_state][SIGMA.iter().position(|&x| x==char ).unwrap()].parse::<u32>().unwrap() as usize;
+        }
+    }
+    for state in &table[len] {
+        if state == &curr_state.to_string() {return true} //valid end state reached!
+    }
+    false //end state not reached
+}
+
+/// For the tests, parsing a regex known to be valid
+#[cfg(test)]
+fn tree(pattern: &str) -> regex::Regex {
+    parse_pattern(pattern).unwrap()
+}
+
+/// For the tests, building a regex known to be valid with Thompson's construction and subset construction
+#[cfg(test)]
+fn build(pattern: &str) -> Dfa {
+    compile(pattern, None, STATE_LIMIT).unwrap()
+}
+
+#[test]
+fn test_scan_regex(){
+    let mut solution = vec!['a','*','b','|','(','c','d','e',')','|','(', 'e','f','g',')'];
+    assert_eq!(scan_regex("a*b|{cde}|(efg)"), Ok(solution));
+
+    solution = vec!['!', '@', 'a', 'b', 'z', '8', '+'];
+    assert_eq!(scan_regex("\\w\\dabz8+"), Ok(solution));
+
+    solution = vec!['!', '+', '~', '(', 'i', 'f', ')', '&', 'a'];
+    assert_eq!(scan_regex("\\w+~{if}&a"), Ok(solution));
+
+    assert!(scan_regex("a\\x").unwrap_err().contains("position 1"));
+    assert!(scan_regex("aB").unwrap_err().contains("not in the alphabet"));
+    assert!(scan_regex("a\\").is_err());
+    assert!(scan_regex("(ab").is_err());
+}
+
+#[test]
+fn test_check_string(){
+    let mut table = Vec::<Vec::<String>>::new();
+    let mut row = vec![String::from("0"), String::from("1"), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" ")]; 
+    table.push(row);
+    row = vec![String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" ")];
+    table.push(row);
+    row = vec![String::from("X"), String::from("1")];
+    table.push(row);
+
+    assert!(check_string("aaaab", &table));
+    assert!(check_string("b", &table));
+    assert!(!check_string("bbbbb", &table));
+    assert!(!check_string("", &table));
+}
+
+#[test]
+fn test_compile(){
+    for construction in [None, Some("thompson"), Some("derivative"), Some("glushkov")] {
+        let dfa = compile("a*b|\\d+", construction, STATE_LIMIT).unwrap();
+        assert!(dfa.accepts("aab") && dfa.accepts("42") && !dfa.accepts("a4"));
+    }
+    assert!(compile("a(b", None, STATE_LIMIT).unwrap_err().starts_with("Invalid regex \"a(b\""));
+    assert!(compile("a&b", Some("glushkov"), STATE_LIMIT).unwrap_err().starts_with("Invalid regex"));
+    assert!(compile("(a|b)*a(a|b)(a|b)", None, 4).unwrap_err().starts_with("Too many states"));
+}
+
+#[test]
+fn test_constructions_agree(){
+    //every string of a, b, 0, and 1 up to length 4, and a few more the patterns below care about
+    let mut inputs = vec![String::new()];
+    for length in 1..=4 {
+        let shorter: Vec<String> = inputs.iter().filter(|s| s.len() == length - 1).cloned().collect();
+        for string in shorter {
+            inputs.extend("ab01".chars().map(|c| format!("{}{}", string, c)));
+        }
+    }
+    inputs.extend(["if", "in", "iff", "cd", "abcd", "ccd", "12 34", "1 ", "aac", "abcabc"].iter().map(|s| s.to_string()));
+
+    let patterns = [
+        "aa+", "a+a+", "(ab)+(ab)+", "(ab|c)*d", "\\w+~(if|in)", "(a|b)*&(\\w*a\\w*)", "a*~(aa)*", "(0|1(01*0)*1)*",
+        "(a|b)*a(a|b)(a|b)", "\\d+( \\d+)*", "(a*b*)+c", "((a|b)+c*)*",
+    ];
+    for pattern in patterns {
+        //every construction, the smallest Dfa, and the regex state elimination writes back accept the same strings
+        let expected = build(pattern);
+        let mut built = vec![
+            ("derivative", compile(pattern, Some("derivative"), STATE_LIMIT).unwrap()),
+            ("minimize", expected.minimize()),
+            ("to-regex", build(&regex::to_pattern(&elimination::dfa_to_regex(&expected)))),
+        ];
+        if let Ok(dfa) = compile(pattern, Some("glushkov"), STATE_LIMIT) {
+            built.push(("glushkov", dfa)); //glushkov can't build & or ~
+        }
+        for (way, dfa) in &built {
+            assert_eq!(expected.product(dfa, |a, b| a != b).shortest_accepted(), None, "{} by {}", pattern, way);
+        }
+
+        //the engines that never build the whole Dfa give the same answers
+        let mut simulator = simulate::Simulator::new(&compile_nfa(pattern, None).unwrap());
+        let mut lazy = lazy::LazyDfa::new(simulate::Simulator::new(&compile_nfa(pattern, None).unwrap()), 3);
+        for input in &inputs {
+            assert_eq!(simulator.accepts(input), expected.accepts(input), "{} on {:?} by simulation", pattern, input);
+            assert_eq!(lazy.accepts(input), expected.accepts(input), "{} on {:?} by the lazy Dfa", pattern, input);
+        }
+    }
+}
@@ -1,148 +1,923 @@
-/// # Project 1 - Ashley Woods
-/// ## Purpose: 
-///     1. Accept a regular expression from the command line.
-///     2. Build an internal representation of the state diagram for the regular expression.
-///     3. Output to stdout the Graphiz definition of the state diagram.
-///     4. Read lines from stdin. The reason for using stdin is that you can either type in lines to test with or produce a text file that you redirect into the program.
-///     5. Each line from the file be a string that will be processed by the state machine.
-///     6. If the string is accepted by the state machine (it matches the regular expression), print “Accept” and the string to stderr.
-///     7. If the string is rejected by the state machine (it doesn’t match the regular expression), print “Reject” and the string to stderr
-/// 
-/// ## Operation:
-///     - To run: cargo run RegEx
-///         - RegEx is the regular expression used to recognize strings
-///     - To exit: ctrl c
-///     - To test: cargo test
-/// 
-/// ## Grammar for a Regular Expression:
-///     E -> C|E    //The '|' character is part of the actual definition here
-///     E -> C
-///     C -> SC|S
-///     S -> P*|P+|P
-///     P -> (E)|{E}|L
-///     L -> \w|\d|A
-///     A -> All accepted characters (sigma)
+//! # Project 1 - Ashley Woods
+//! ## Purpose: 
+//!     1. Accept a regular expression from the command line.
+//!     2. Build an internal representation of the state diagram for the regular expression.
+//!     3. Output to stdout the Graphiz definition of the state diagram.
+//!     4. Read lines from stdin. The reason for using stdin is that you can either type in lines to test with or produce a text file that you redirect into the program.
+//!     5. Each line from the file be a string that will be processed by the state machine.
+//!     6. If the string is accepted by the state machine (it matches the regular expression), print “Accept” and the string to stderr.
+//!     7. If the string is rejected by the state machine (it doesn’t match the regular expression), print “Reject” and the string to stderr
+//! 
+//! ## Operation:
+//!     - To run: cargo run RegEx
+//!         - RegEx is the regular expression used to recognize strings
+//!     - To compare: cargo run equiv RegEx1 RegEx2
+//!         - Prints whether both accept the same strings, or a shortest string only one of them accepts
+//!     - To check inclusion: cargo run subset RegEx1 RegEx2
+//!         - Prints whether every string RegEx1 accepts is accepted by RegEx2, or a shortest string that is not
+//!     - To list matches: cargo run gen --max-len N RegEx
+//!         - Prints every accepted string up to length N, shortest first then in SIGMA order, at most --max-count of them (1000 by default)
+//!     - To make test strings: cargo run sample --len N RegEx
+//!         - Prints --count (10 by default) accepted strings of length N picked at random, all equally likely; --seed S repeats a run, --near-miss adds a rejected string one edit away from each
+//!         - The output can be piped back in: cargo run sample --len 5 --near-miss RegEx | cargo run RegEx
+//!     - To analyze: cargo run analyze RegEx
+//!         - Prints whether no strings, finitely many, or infinitely many are accepted, the shortest and longest accepted strings, and how many are accepted of each length up to --max-len (10 by default)
+//!     - To get a regex back: cargo run to-regex --import-json File (or --import-jff File, --import-bin File, or a RegEx with --and, --or, or --not)
+//!         - Prints a regex in this syntax accepting the same strings, found by state elimination; the empty string is written (a~a)* and no strings a~a
+//!     - To check many regexes at once: cargo run set RegEx1 RegEx2 ... (and/or --patterns File with one regex per line)
+//!         - Numbers the regexes from 0, builds one automaton for all of them, and prints "Matched" with the numbers of the regexes each stdin line matches (or "none") to stderr
+//!     - To tokenize: cargo run lex --rules File
+//!         - File has one NAME = regex rule per line (# starts a comment, trailing spaces are part of the regex); each stdin line is split into the longest tokens any rule matches, the earlier rule winning a tie, printed as (NAME, "lexeme", line, column)
+//!     - To try patterns interactively: cargo run repl (or cargo run repl RegEx to start with one)
+//!         - Each stdin line is a command or a string to check: :regex RegEx, :dot, :table, :min (fewest states), :gen N, :trace String, :help, :quit; other lines print Accept or Reject; regexes are built from the syntax tree with --construction (thompson by default) and a mistake in one is reported without ending the session
+//!     - To generate code: cargo run codegen --lang rust|c|js RegEx
+//!         - Prints a standalone function (named by --name, which can't be a keyword of the language: matches by default in Rust, match in C and JavaScript) that accepts exactly the strings the automaton does; C reads bytes, so anything outside SIGMA rejects in every language
+//!     - Options:
+//!         - --export-json File: also save the automaton to File as JSON (schema in src/json.rs)
+//!         - --import-json File: load the automaton from File instead of a RegEx, then test stdin lines against it
+//!         - --export-jff File: also save the automaton to File in JFLAP's .jff format
+//!         - --import-jff File: load a JFLAP finite automaton (DFA or NFA) from File instead of a RegEx
+//!         - --export-bin File: also save the automaton to File in a compact binary format with a checksum (layout in src/binary.rs)
+//!         - --import-bin File: load a binary automaton from File instead of a RegEx
+//!         - --cache-dir Dir: keep the automaton built for the RegEx in Dir, named by a hash of the RegEx, --construction, and alphabet, and load it from there the next time instead of building it; --and, --or, and --not are still applied after
+//!         - --format dot|mermaid|plantuml|tikz: write the state diagram to stdout.txt as Graphviz (default), a Mermaid stateDiagram-v2, PlantUML, or a LaTeX TikZ picture
+//!         - --table markdown|csv|ascii: print the transition table with only the symbols used, start state marked → and accepting states marked *
+//!         - --and RegEx2: only accept strings RegEx2 also accepts (intersection)
+//!         - --or RegEx2: also accept strings RegEx2 accepts (union)
+//!         - --not: accept exactly the strings that would otherwise be rejected (complement), applied after --and and --or
+//!         - --reverse: reverse the automaton so it accepts the accepted strings written backwards, applied last (useful with --format or to-regex)
+//!         - --spans: instead of Accept or Reject, print "Match start-end text" for each leftmost-longest match in a stdin line (found with the reversed automaton), or "No match"
+//!         - --simplify: print a smaller regex accepting the same strings, like a* for (a*)* or \d for \d|0, checked against the original (then exits)
+//!         - --construction thompson|derivative|glushkov: build automata from the regex syntax tree, by Thompson's construction and subset construction, by Brzozowski derivatives (each state is what is left of the regex to match), or by Glushkov's position automaton (no epsilon moves, one state per symbol in the regex, no & or ~), instead of the original parser; commands always build this way, with thompson unless another is given
+//!         - --engine dfa|nfa|lazy: match stdin lines with the transition table (default), by simulating the NFA one set of states at a time, or with a DFA whose states are only built when a line reaches them; nfa and lazy never build the table (no table, diagram, or exports are printed)
+//!         - --state-limit N: most states subset construction may make, or the combined automaton of set or lex may have (10000 by default); past it stdin lines are matched by simulating the NFA instead, or with --and, --or, --not, --reverse, --spans, or a command the run stops with an error
+//!         - --cache-size N: most states the lazy engine keeps (1000 by default); when full they are all dropped and built again as needed
+//!     - To exit: ctrl c
+//!     - To match a fixed regex in another Rust program: depend on the dfa_macro crate (in dfa_macro/) and write dfa_macro::dfa!("RegEx")
+//!         - Builds the automaton while compiling (Thompson's construction), giving a fn(&str) -> bool backed by a static transition table; an invalid RegEx is a compile error
+//!     - To test: cargo test --workspace
+//! 
+//! ## Grammar for a Regular Expression:
+//!     B -> B&E|B~E|E    //'&' is intersection, '~' is difference (in the left but not the right)
+//!     E -> C|E    //The '|' character is part of the actual definition here
+//!     E -> C
+//!     C -> SC|S
+//!     S -> P*|P+|P
+//!     P -> (B)|{B}|L
+//!     L -> \w|\d|A
+//!     A -> All accepted characters (sigma)
 
 
 use std::io::Write; //for writing to output file and stderr
 use std::fs::File; //for creating output file
 use std::io::stdin; //for reading from stdin
 use std::io::prelude::*; //for reading from stdin
-
-//define SIGMA and additionall acceptable chars for refrence
-const SIGMA: [char; 37] = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z','0','1','2','3','4','5','6','7','8','9', ' '];
-const REG_CHARS: [char; 8] = ['|', '{', '}', '(', ')', '*', '+', '\\'];
+use std::io::IsTerminal; //for only prompting a person in the repl
+
+use awoods_project1::{binary, codegen, diagram, dfa, elimination, jflap, json, lazy, lexer, nfa, regex, regex_set, sample, simplify, simulate, spans, table, thompson}; //the automata and everything built on them, in src/lib.rs
+use awoods_project1::{check_string, compile, compile_nfa, parse_pattern, scan_regex, SIGMA, STATE_LIMIT};
+mod repl; //interactive mode
+
+use dfa::Dfa;
+use nfa::Nfa;
+
+//define additionall acceptable chars for refrence, SIGMA is in src/lib.rs
+const REG_CHARS: [char; 10] = ['|', '{', '}', '(', ')', '*', '+', '\\', '&', '~'];
+const FORMATS: [&str; 4] = ["dot", "mermaid", "plantuml", "tikz"]; //state diagram formats for --format
+const TABLES: [&str; 3] = ["markdown", "csv", "ascii"]; //transition table layouts for --table
+const CONSTRUCTIONS: [&str; 3] = ["thompson", "derivative", "glushkov"]; //ways to build the automaton for --construction
+const ENGINES: [&str; 3] = ["dfa", "nfa", "lazy"]; //ways to match stdin lines for --engine
+const LANGS: [&str; 3] = ["rust", "c", "js"]; //languages for codegen --lang
+const COMMANDS: [&str; 10] = ["equiv", "subset", "gen", "sample", "analyze", "to-regex", "set", "lex", "codegen", "repl"]; //subcommands that run instead of testing stdin lines
+
+/// Settings picked on the command line
+/// - command: Which of COMMANDS to run (None to test stdin lines)
+/// - reg_ex: The regular expression used to recognize strings (None when the automaton is imported)
+/// - reg_ex_2: The second regular expression, for commands that compare two
+/// - export_json: File to save the automaton to as JSON
+/// - import_json: File to load the automaton from instead of building it from a regex
+/// - export_jff: File to save the automaton to in JFLAP's format
+/// - import_jff: File to load a JFLAP automaton from instead of building it from a regex
+/// - export_bin: File to save the automaton to in the binary format
+/// - import_bin: File to load a binary automaton from instead of building it from a regex
+/// - cache_dir: Directory to keep built automata in, so the same regex is loaded instead of built again
+/// - format: Which of FORMATS to write the state diagram in (None for dot)
+/// - table: Which of TABLES to print the transition table in (None for the raw table)
+/// - and: A regex the strings must also match
+/// - or: A regex the strings may match instead
+/// - not: Flip the result, accepting exactly the strings that would be rejected
+/// - max_len: Longest string the gen command lists, or the analyze command counts (None for 10)
+/// - max_count: Most strings the gen command lists (None for 1000)
+/// - len: Length of the strings the sample command picks
+/// - count: How many strings the sample command picks (None for 10)
+/// - seed: Seed for the sample command's random numbers (None to seed from the clock)
+/// - near_miss: Also have the sample command print a rejected string close to each accepted one
+/// - simplify: Print a simplified form of the regex instead of building its automaton
+/// - construction: How to build automata from regexes, one of CONSTRUCTIONS (None for parse_regex, or Thompson's for & and ~)
+/// - engine: How to match stdin lines, one of ENGINES (None for dfa)
+/// - state_limit: Most states subset construction may make before giving up (None for 10000)
+/// - cache_size: Most states the lazy engine keeps before flushing (None for 1000)
+/// - reverse: Reverse the automaton, so it accepts the accepted strings written backwards
+/// - spans: Print where each stdin line matches instead of whether the whole line does
+/// - patterns: The regexes for the set command
+/// - patterns_file: File with more regexes for the set command, one per line
+/// - rules: File of NAME = regex rules for the lex command
+/// - lang: Language the codegen command writes, one of LANGS
+/// - name: Name of the function the codegen command writes (None for matches in Rust and match in C and JavaScript)
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Options {
+    command: Option<String>,
+    reg_ex: Option<String>,
+    reg_ex_2: Option<String>,
+    export_json: Option<String>,
+    import_json: Option<String>,
+    export_jff: Option<String>,
+    import_jff: Option<String>,
+    export_bin: Option<String>,
+    import_bin: Option<String>,
+    cache_dir: Option<String>,
+    format: Option<String>,
+    table: Option<String>,
+    and: Option<String>,
+    or: Option<String>,
+    not: bool,
+    max_len: Option<usize>,
+    max_count: Option<usize>,
+    len: Option<usize>,
+    count: Option<usize>,
+    seed: Option<u64>,
+    near_miss: bool,
+    simplify: bool,
+    construction: Option<String>,
+    engine: Option<String>,
+    state_limit: Option<usize>,
+    cache_size: Option<usize>,
+    reverse: bool,
+    spans: bool,
+    patterns: Vec<String>,
+    patterns_file: Option<String>,
+    rules: Option<String>,
+    lang: Option<String>,
+    name: Option<String>,
+}
 
 fn main() {
 
     //Grab input from command line
-    let mut args = Vec::new();
-    for input in std::env::args().skip(1) {
-		args.push(input);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match parse_options(&args) {
+        Some(options) => options,
+        None => {
+            eprintln!("Invalid Input");
+            std::process::exit(1);
+        }
+    };
+    let options = if options.command.is_some() {tree_options(&options)} else {options};
+
+    //Run a command comparing two regexes instead, if one was given
+    if let (Some(reg_ex_1), Some(reg_ex_2)) = (&options.reg_ex, &options.reg_ex_2) {
+        if options.command.as_deref() == Some("equiv") {
+            equiv(reg_ex_1, reg_ex_2, &options);
+        }
+        else {
+            subset(reg_ex_1, reg_ex_2, &options);
+        }
+        return;
     }
 
-    //Check that there is only one input
-    if args.len() != 1 {
-		writeln!(std::io::stderr(), "Invalid Input").unwrap();
-		std::process::exit(1);
-	}
+    //Check every line against a set of regexes instead, if asked
+    if options.command.as_deref() == Some("set") {
+        match_set(&options);
+        return;
+    }
 
-    //Get the regular expression
-    let reg_ex = &args[0];
+    //Split stdin into tokens instead, if asked
+    if options.command.as_deref() == Some("lex") {
+        lex(&options);
+        return;
+    }
 
-    //check that input is valid
-    for char in reg_ex.chars() {
-        if !SIGMA.contains(&char) { //if the character is not in sigma
-            if !REG_CHARS.contains(&char) { //and if the character is not in RegEx chars
-                //Then contains unsupported characters and is invalid
-                writeln!(std::io::stderr(), "Invalid Input").unwrap();
-		        std::process::exit(1);
+    //Read commands and strings one at a time instead, if asked
+    if options.command.as_deref() == Some("repl") {
+        repl(&options);
+        return;
+    }
+
+    //Only print the simplified regex, if asked
+    if options.simplify {
+        match &options.reg_ex {
+            Some(reg_ex) if options.command.is_none() => simplify_regex(reg_ex),
+            _ => {
+                eprintln!("Invalid Input");
+                std::process::exit(1);
             }
         }
+        return;
     }
 
-    //Scan reg_ex: method call, input regex, output vec with translation
-    let scanned_reg_ex = scan_regex(reg_ex);
-    //println!("{:?}", scanned_reg_ex);
+    //Match stdin lines by simulating the NFA instead of building a table, if asked
+    if options.engine.as_deref() == Some("nfa") {
+        let mut simulator = simulate::Simulator::new(&load_nfa(&options));
+        process_input(|string| simulator.accepts(string));
+        return;
+    }
+    if options.engine.as_deref() == Some("lazy") {
+        let mut lazy = lazy::LazyDfa::new(simulate::Simulator::new(&load_nfa(&options)), options.cache_size.unwrap_or(1000));
+        process_input(|string| lazy.accepts(string));
+        if lazy.flushes > 0 {
+            eprintln!("The lazy DFA cache filled and was flushed {} times, a bigger --cache-size may be faster", lazy.flushes);
+        }
+        return;
+    }
 
-    //Parse reg_ex: method call, input regex, output transition diagram if failed parse print error and exit
-    let trans_table = parse_regex(scanned_reg_ex);
-    println!("Trans_table: \n {:?}", trans_table);
+    //Get the transition table, either loaded from a file or built from the regular expression
+    let (pattern, trans_table) = load_automaton(&options);
+
+    //Run a command on the automaton instead of testing stdin lines, if one was given
+    if options.command.as_deref() == Some("gen") {
+        let dfa = Dfa::from_table(&pattern, &trans_table);
+        for string in dfa.enumerate(options.max_len.unwrap(), options.max_count.unwrap_or(1000)) {
+            println!("{}", string);
+        }
+        return;
+    }
+    if options.command.as_deref() == Some("analyze") {
+        analyze(&Dfa::from_table(&pattern, &trans_table), options.max_len.unwrap_or(10));
+        return;
+    }
+    if options.command.as_deref() == Some("to-regex") {
+        let dfa = Dfa::from_table(&pattern, &trans_table);
+        println!("{}", regex::to_pattern(&elimination::dfa_to_regex(&dfa)));
+        return;
+    }
+    if options.command.as_deref() == Some("sample") {
+        sample(&Dfa::from_table(&pattern, &trans_table), &options);
+        return;
+    }
+    if options.command.as_deref() == Some("codegen") {
+        let dfa = Dfa::from_table(&pattern, &trans_table);
+        let code = match options.lang.as_deref() {
+            Some("c") => codegen::c(&dfa, options.name.as_deref().unwrap_or("match")),
+            Some("js") => codegen::javascript(&dfa, options.name.as_deref().unwrap_or("match")),
+            _ => codegen::rust(&dfa, options.name.as_deref().unwrap_or("matches")),
+        };
+        print!("{}", code);
+        return;
+    }
+
+    //Print the transition table, in a readable layout if one was picked
+    match &options.table {
+        Some(style) => print!("{}", table::format_table(&Dfa::from_table(&pattern, &trans_table), style)),
+        None => println!("Trans_table: \n {:?}", trans_table),
+    }
 
     //Print the state diagram to stdout
     //Also do so in another method input is diagram, no return, it creates the file
-    print_state_diagram(&trans_table);
+    match options.format.as_deref() {
+        Some("mermaid") => write_file("stdout.txt", &diagram::mermaid(&Dfa::from_table(&pattern, &trans_table))),
+        Some("plantuml") => write_file("stdout.txt", &diagram::plantuml(&Dfa::from_table(&pattern, &trans_table))),
+        Some("tikz") => write_file("stdout.txt", &diagram::tikz(&Dfa::from_table(&pattern, &trans_table))),
+        _ => print_state_diagram(&trans_table),
+    }
+
+    //Save the automaton if asked to
+    if let Some(path) = &options.export_json {
+        write_file(path, &json::dfa_to_json(&Dfa::from_table(&pattern, &trans_table)));
+    }
+    if let Some(path) = &options.export_jff {
+        write_file(path, &jflap::nfa_to_jff(&Nfa::from_dfa(&Dfa::from_table(&pattern, &trans_table))));
+    }
+    if let Some(path) = &options.export_bin {
+        write_binary_file(path, &binary::dfa_to_bytes(&Dfa::from_table(&pattern, &trans_table)));
+    }
  
-    //Read from stdin and print to stderr
-    process_input(trans_table);
+    //Read from stdin and print to stderr, either where each line matches or whether it does
+    if options.spans {
+        process_spans(&spans::SpanFinder::new(&Dfa::from_table(&pattern, &trans_table)));
+    }
+    else {
+        process_input(|string| check_string(string, &trans_table));
+    }
 
 }
 
+/// For reading the command line arguments into Options
+/// - Input: The arguments, not including the program name
+/// - Output: The options, or None if the arguments are invalid
+fn parse_options(args: &[String]) -> Option<Options> {
+    let mut options = Options::default();
+    let mut positional = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        //flags that take a file name after them
+        let value = args.get(i + 1).cloned();
+        match args[i].as_str() {
+            "--export-json" => options.export_json = Some(value?),
+            "--import-json" => options.import_json = Some(value?),
+            "--export-jff" => options.export_jff = Some(value?),
+            "--import-jff" => options.import_jff = Some(value?),
+            "--export-bin" => options.export_bin = Some(value?),
+            "--import-bin" => options.import_bin = Some(value?),
+            "--cache-dir" => options.cache_dir = Some(value?),
+            "--patterns" => options.patterns_file = Some(value?),
+            "--rules" => options.rules = Some(value?),
+            "--name" => {
+                let name = value?;
+                //the name goes straight into the code, so it has to be an identifier
+                if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                    return None;
+                }
+                options.name = Some(name);
+            }
+            "--lang" => {
+                let lang = value?;
+                if !LANGS.contains(&lang.as_str()) {
+                    return None;
+                }
+                options.lang = Some(lang);
+            }
+            "--max-len" => options.max_len = Some(value?.parse().ok()?),
+            "--max-count" => options.max_count = Some(value?.parse().ok()?),
+            "--len" => options.len = Some(value?.parse().ok()?),
+            "--count" => options.count = Some(value?.parse().ok()?),
+            "--seed" => options.seed = Some(value?.parse().ok()?),
+            "--and" => options.and = Some(value?),
+            "--or" => options.or = Some(value?),
+            "--not" => {
+                options.not = true;
+                i += 1;
+                continue;
+            }
+            "--near-miss" => {
+                options.near_miss = true;
+                i += 1;
+                continue;
+            }
+            "--simplify" => {
+                options.simplify = true;
+                i += 1;
+                continue;
+            }
+            "--reverse" => {
+                options.reverse = true;
+                i += 1;
+                continue;
+            }
+            "--spans" => {
+                options.spans = true;
+                i += 1;
+                continue;
+            }
+            "--format" => {
+                let format = value?;
+                if !FORMATS.contains(&format.as_str()) {
+                    return None;
+                }
+                options.format = Some(format);
+            }
+            "--construction" => {
+                let construction = value?;
+                if !CONSTRUCTIONS.contains(&construction.as_str()) {
+                    return None;
+                }
+                options.construction = Some(construction);
+            }
+            "--engine" => {
+                let engine = value?;
+                if !ENGINES.contains(&engine.as_str()) {
+                    return None;
+                }
+                options.engine = Some(engine);
+            }
+            "--state-limit" => options.state_limit = Some(value?.parse().ok()?),
+            "--cache-size" => options.cache_size = Some(value?.parse().ok()?),
+            "--table" => {
+                let style = value?;
+                if !TABLES.contains(&style.as_str()) {
+                    return None;
+                }
+                options.table = Some(style);
+            }
+            _ => {
+                positional.push(args[i].clone());
+                i += 1;
+                continue;
+            }
+        }
+        i += 2;
+    }
 
-/// For scanning the input regex into a vector of symbols easier to parse
-/// - Input: Regex string to scan
-/// - Output: Vector containing scanned string
-/// - KEY:
-///     - SIGMA -> SIGMA
-///     - \w -> !
-///     - \d -> @
-///     - (,{ -> (
-///     - ),} -> {
-///     - '*' -> *
-///     - '+' -> +
-///     - | -> |
-fn scan_regex(reg: &str) -> Vec<char>{
-    let mut scanned = Vec::new();
-    let mut special_char = false;
-    let mut paren_count = 0; //used to make sure each ( and { has a matching ) or }
-    for char in reg.chars(){
-        if special_char { // if the previous symbol was a '\'
-            if char == 'w' {
-                scanned.push('!');
-                special_char = false;
-            }
-            else if char == 'd' {
-                scanned.push('@');
-                special_char = false;
+    //Commands come first and take their regexes after them
+    if !positional.is_empty() && COMMANDS.contains(&positional[0].as_str()) {
+        options.command = Some(positional.remove(0));
+    }
+    match options.command.as_deref() {
+        Some("equiv") | Some("subset") => {
+            if positional.len() != 2 {
+                return None;
             }
-            else {
-                //ERROR, not a \w or \d, not a valid regex
-                writeln!(std::io::stderr(), "Invalid Input").unwrap();
+            options.reg_ex_2 = positional.pop();
+            options.reg_ex = positional.pop();
+            return Some(options);
+        }
+        Some("gen") => {
+            options.max_len?; //gen needs to know when to stop
+        }
+        Some("codegen") => {
+            let lang = options.lang.as_ref()?;
+            //the name has to be one the language lets a function have
+            if options.name.as_ref().is_some_and(|name| codegen::is_reserved(name, lang)) {
+                return None;
+            }
+        }
+        Some("repl") => {
+            //the regex is optional, one can be given with :regex
+            if positional.len() > 1 || options.import_json.is_some() || options.import_jff.is_some() || options.import_bin.is_some() {
+                return None;
+            }
+            options.reg_ex = positional.pop();
+            return Some(options);
+        }
+        Some("lex") => {
+            //the rules file holds all the regexes
+            if !positional.is_empty() || options.rules.is_none() || options.import_json.is_some() || options.import_jff.is_some() || options.import_bin.is_some() {
+                return None;
+            }
+            return Some(options);
+        }
+        Some("set") => {
+            //any number of regexes, as long as there is at least one from here or the file
+            if positional.is_empty() && options.patterns_file.is_none() || options.import_json.is_some() || options.import_jff.is_some() || options.import_bin.is_some() {
+                return None;
+            }
+            options.patterns = positional;
+            return Some(options);
+        }
+        Some("sample") => {
+            options.len?;
+        }
+        _ => {}
+    }
+
+    //Simulating the NFA only matches stdin lines, there is no table for the rest
+    if matches!(options.engine.as_deref(), Some("nfa") | Some("lazy")) && (options.command.is_some() || options.and.is_some() || options.or.is_some() || options.not || options.reverse || options.spans) {
+        return None;
+    }
+
+    //Check that there is only one input, or none if the automaton is being imported
+    if options.import_json.is_some() || options.import_jff.is_some() || options.import_bin.is_some() {
+        let imports = [&options.import_json, &options.import_jff, &options.import_bin].iter().filter(|path| path.is_some()).count();
+        if !positional.is_empty() || imports > 1 {
+            return None;
+        }
+    }
+    else if positional.len() == 1 {
+        options.reg_ex = positional.pop();
+    }
+    else {
+        return None;
+    }
+    Some(options)
+}
+
+/// For getting the transition table the options ask for
+/// Loads it from a file or the --cache-dir cache, or builds it from the regex, then applies --and, --or, and --not
+/// Prints an error and exits if the file or regex is invalid
+/// - Input: The options
+/// - Output: The regex the automaton came from, and the transition table
+fn load_automaton(options: &Options) -> (String, Vec<Vec<String>>) {
+    let pattern;
+    let trans_table;
+    if let Some(path) = &options.import_json {
+        let dfa = match json::dfa_from_json(&read_file(path)) {
+            Ok(dfa) => dfa,
+            Err(message) => {
+                eprintln!("Invalid JSON in {}: {}", path, message);
+                std::process::exit(1);
+            }
+        };
+        pattern = dfa.pattern.clone();
+        trans_table = dfa.to_table();
+    }
+    else if let Some(path) = &options.import_jff {
+        let nfa = match jflap::nfa_from_jff(&read_file(path)) {
+            Ok(nfa) => nfa,
+            Err(message) => {
+                eprintln!("Invalid JFLAP file {}: {}", path, message);
+                std::process::exit(1);
+            }
+        };
+        pattern = String::new(); //JFLAP files don't record a regex
+        trans_table = nfa.to_dfa(&pattern).to_table();
+    }
+    else if let Some(path) = &options.import_bin {
+        let dfa = match binary::dfa_from_bytes(&read_binary_file(path)) {
+            Ok(dfa) => dfa,
+            Err(message) => {
+                eprintln!("Invalid binary automaton in {}: {}", path, message);
+                std::process::exit(1);
+            }
+        };
+        pattern = dfa.pattern.clone();
+        trans_table = dfa.to_table();
+    }
+    else if let Some(dfa) = options.cache_dir.as_ref().and_then(|dir| load_cached(dir, options)) {
+        pattern = dfa.pattern.clone();
+        trans_table = dfa.to_table();
+    }
+    else {
+        pattern = options.reg_ex.clone().unwrap();
+        //Fall back to simulating the NFA if subset construction makes too many states, when only stdin lines need matching
+        let mut built = None;
+        if can_simulate(options) && (options.construction.is_some() || needs_tree(&pattern)) {
+            let limit = options.state_limit.unwrap_or(STATE_LIMIT);
+            match compile(&pattern, options.construction.as_deref(), limit) {
+                Ok(dfa) => built = Some(dfa.to_table()),
+                Err(message) => match compile_nfa(&pattern, options.construction.as_deref()) {
+                    //the NFA builds, so it was subset construction that passed the limit
+                    Ok(nfa) => {
+                        eprintln!("Subset construction passed {} states, matching by simulating the NFA instead", limit);
+                        let mut simulator = simulate::Simulator::new(&nfa);
+                        process_input(|string| simulator.accepts(string));
+                        std::process::exit(0);
+                    }
+                    Err(_) => {
+                        eprintln!("{}", message);
+                        std::process::exit(1);
+                    }
+                },
+            }
+        }
+        trans_table = built.unwrap_or_else(|| build_table(&pattern, options));
+        if let Some(dir) = &options.cache_dir {
+            //use the table as it comes back from the cache, so the output is the same the next time
+            let dfa = Dfa::from_table(&pattern, &trans_table);
+            store_cached(dir, options, &dfa);
+            return combine(pattern, dfa.to_table(), options);
+        }
+    }
+
+    //Combine with the other regexes given, --and and --or first and then --not of the whole thing
+    combine(pattern, trans_table, options)
+}
+
+/// For loading the automaton of the regex from the --cache-dir cache
+/// A missing file just means it has not been built yet, a damaged one is reported and built again
+/// - Input: The cache directory, and the options holding the regex and construction
+/// - Output: The Dfa, or None if it has to be built
+fn load_cached(dir: &str, options: &Options) -> Option<Dfa> {
+    let reg_ex = options.reg_ex.as_ref()?;
+    let path = std::path::Path::new(dir).join(binary::cache_name(reg_ex, options.construction.as_deref()));
+    let bytes = std::fs::read(&path).ok()?;
+    match binary::dfa_from_bytes(&bytes) {
+        Ok(dfa) if &dfa.pattern == reg_ex => Some(dfa),
+        Ok(_) => None, //two regexes with the same hash, the other one is kept
+        Err(message) => {
+            eprintln!("Ignoring cached automaton {}: {}", path.display(), message);
+            None
+        }
+    }
+}
+
+/// For saving the automaton of the regex in the --cache-dir cache, creating the directory if needed
+/// Failing to save only prints a warning, the automaton was built either way
+/// - Input: The cache directory, the options holding the regex and construction, and the Dfa
+/// - Output: None
+fn store_cached(dir: &str, options: &Options, dfa: &Dfa) {
+    let path = std::path::Path::new(dir).join(binary::cache_name(&dfa.pattern, options.construction.as_deref()));
+    if std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, binary::dfa_to_bytes(dfa))).is_err() {
+        eprintln!("Unable to write cached automaton {}", path.display());
+    }
+}
+
+/// For describing the strings an automaton accepts: none, finitely many, or infinitely many,
+/// how many there are of each length, and the shortest and longest
+/// - Input: The automaton, and the longest length to count strings of
+/// - Output: None, the report is printed to stdout
+fn analyze(dfa: &Dfa, max_len: usize) {
+    let shortest = dfa.shortest_accepted();
+    if shortest.is_none() {
+        println!("Language: empty, no strings are accepted");
+        return;
+    }
+    if dfa.is_finite() {
+        //no accepted string is as long as the number of states, or it would have gone round a loop
+        let counts = dfa.count_accepted(dfa.transitions.len());
+        let total = counts.iter().fold(0u128, |total, row| total.saturating_add(row[dfa.start]));
+        println!("Language: finite, {} string{} accepted", total, if total == 1 {""} else {"s"});
+    }
+    else {
+        println!("Language: infinite");
+    }
+    println!("Shortest: {:?}", shortest.unwrap());
+    match dfa.longest_accepted() {
+        Some(longest) => println!("Longest: {:?}", longest),
+        None => println!("Longest: none, accepted strings can be any length"),
+    }
+    println!("Accepted strings of each length:");
+    for (length, row) in dfa.count_accepted(max_len).iter().enumerate() {
+        if row[dfa.start] == u128::MAX {
+            println!("    {}: more than {}", length, u128::MAX - 1);
+        }
+        else {
+            println!("    {}: {}", length, row[dfa.start]);
+        }
+    }
+}
+
+/// For printing random accepted strings of one length, ready to pipe back in as test lines
+/// With --near-miss a rejected string one edit away from each accepted string is printed after them
+/// - Input: The automaton, and the options with the length, count, seed, and near_miss
+/// - Output: None, the strings are printed to stdout one per line
+fn sample(dfa: &Dfa, options: &Options) {
+    let len = options.len.unwrap();
+    let mut rng = match options.seed {
+        Some(seed) => sample::Rng::new(seed),
+        None => sample::Rng::from_time(),
+    };
+    let counts = dfa.count_accepted(len);
+    let mut accepted = Vec::new();
+    for _ in 0..options.count.unwrap_or(10) {
+        match sample::sample_accepted(dfa, &counts, len, &mut rng) {
+            Ok(string) => accepted.push(string),
+            Err(message) => {
+                eprintln!("{}", message);
+                std::process::exit(1);
+            }
+        }
+    }
+    for string in &accepted {
+        println!("{}", string);
+    }
+    if options.near_miss {
+        for string in &accepted {
+            if let Some(miss) = sample::near_miss(dfa, string, &mut rng) {
+                println!("{}", miss);
+            }
+        }
+    }
+}
+
+/// For checking that a regular expression only uses supported characters, prints an error and exits if not
+fn check_chars(reg_ex: &str) {
+    for char in reg_ex.chars() {
+        if !SIGMA.contains(&char) { //if the character is not in sigma
+            if !REG_CHARS.contains(&char) { //and if the character is not in RegEx chars
+                //Then contains unsupported characters and is invalid
+                eprintln!("Invalid Input");
 		        std::process::exit(1);
             }
         }
-        else if SIGMA.contains(&char){
-            scanned.push(char); //push any alphabet characters straight to the vec
+    }
+}
+
+/// For scanning a regular expression from the command line, prints an error and exits if it is invalid
+/// - Input: String containing the regex
+/// - Output: Vector containing scanned string, see scan_regex
+fn scan(reg_ex: &str) -> Vec<char> {
+    match scan_regex(reg_ex) {
+        Ok(scanned) => scanned,
+        Err(_) => {
+            eprintln!("Invalid Input");
+            std::process::exit(1);
         }
-        else if char == '\\' {
-            special_char = true; //the next char must be a w or a d
+    }
+}
+
+/// For turning a regular expression from the command line into a syntax tree, prints an error and exits if it is invalid
+/// - Input: String containing the regex
+/// - Output: The tree
+fn parse_tree(reg_ex: &str) -> regex::Regex {
+    check_chars(reg_ex);
+    match parse_pattern(reg_ex) {
+        Ok(tree) => tree,
+        Err(_) => {
+            eprintln!("Invalid Input");
+            std::process::exit(1);
         }
-        else if char == '(' || char == '{' {
-            scanned.push('(');
-            paren_count += 1; //enter a paren
+    }
+}
+
+/// For the --simplify option, prints a smaller regex accepting the same strings
+/// The result is checked by building automata for both and searching for a string only one accepts
+/// - Input: The regular expression
+/// - Output: None, the simplified regex is printed to stdout and the check to stderr
+fn simplify_regex(reg_ex: &str) {
+    let tree = parse_tree(reg_ex);
+    let simpler = simplify::simplify(&tree);
+    let pattern = regex::to_pattern(&simpler);
+    println!("{}", pattern);
+
+    let before = thompson::thompson(&tree).to_dfa(reg_ex);
+    let after = thompson::thompson(&simpler).to_dfa(&pattern);
+    match before.product(&after, |a, b| a != b).shortest_accepted() {
+        None => eprintln!("Checked: accepts the same strings as {}", reg_ex),
+        Some(witness) => {
+            eprintln!("Simplifying changed the strings accepted: \"{}\" is accepted by only one of them", witness);
+            std::process::exit(1);
         }
-        else if char == ')' || char == '}' {
-            scanned.push(')');
-            paren_count -= 1; //exit a paren
+    }
+}
+
+/// For checking whether a regular expression has to be built from the syntax tree with no --construction
+/// Intersection and difference can't be built by parse_regex, so regexes using & or ~ are
+/// - Input: The regular expression
+/// - Output: True if it uses & or ~
+fn needs_tree(reg_ex: &str) -> bool {
+    check_chars(reg_ex);
+    let scanned_reg_ex = scan(reg_ex);
+    scanned_reg_ex.contains(&'&') || scanned_reg_ex.contains(&'~')
+}
+
+/// For building the automaton of a regular expression from the syntax tree, prints the error and exits if it can't be built
+/// - Input: The regular expression, and the options saying how to build it
+/// - Output: The Dfa
+fn compile_or_exit(reg_ex: &str, options: &Options) -> Dfa {
+    match compile(reg_ex, options.construction.as_deref(), options.state_limit.unwrap_or(STATE_LIMIT)) {
+        Ok(dfa) => dfa,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
         }
-        else if char == '*'{
-            scanned.push('*');
+    }
+}
+
+/// For turning a regular expression from the command line into a transition table
+/// Prints an error and exits if the regex is invalid, or subset construction passes --state-limit
+/// - Input: The regular expression, and the options saying how to build it
+/// - Output: The transition table built by parse_regex, or by the picked construction
+fn build_table(reg_ex: &str, options: &Options) -> Vec<Vec<String>> {
+    //check that input is valid
+    check_chars(reg_ex);
+
+    //Build from the syntax tree instead, if a construction was picked or is needed
+    if options.construction.is_some() || needs_tree(reg_ex) {
+        return compile_or_exit(reg_ex, options).to_table();
+    }
+
+    //Scan reg_ex: method call, input regex, output vec with translation
+    let scanned_reg_ex = scan(reg_ex);
+    //println!("{:?}", scanned_reg_ex);
+
+    //Parse reg_ex: method call, input regex, output transition diagram if failed parse print error and exit
+    parse_regex(scanned_reg_ex)
+}
+
+/// For deciding whether stdin lines can be matched by simulating the NFA when subset construction passes --state-limit
+/// The simulator only answers Accept or Reject for the regex itself, so anything that changes the automaton after
+/// it is built (--and, --or, --not, --reverse), needs the table (--spans, commands), or builds without an NFA
+/// (--construction derivative) stops with the error instead
+/// - Input: The options
+/// - Output: True if the fallback can be used
+fn can_simulate(options: &Options) -> bool {
+    options.command.is_none() && options.and.is_none() && options.or.is_none() && !options.not && !options.reverse && !options.spans
+        && options.construction.as_deref() != Some("derivative")
+}
+
+/// For loading the NFA to simulate for --engine nfa or lazy
+/// Imported JSON is already a Dfa, and regexes without an NFA construction use Thompson's
+/// - Input: The options
+/// - Output: The Nfa
+fn load_nfa(options: &Options) -> Nfa {
+    if options.import_json.is_some() || options.import_bin.is_some() {
+        let (pattern, trans_table) = load_automaton(options);
+        return Nfa::from_dfa(&Dfa::from_table(&pattern, &trans_table));
+    }
+    if let Some(path) = &options.import_jff {
+        return match jflap::nfa_from_jff(&read_file(path)) {
+            Ok(nfa) => nfa,
+            Err(message) => {
+                eprintln!("Invalid JFLAP file {}: {}", path, message);
+                std::process::exit(1);
+            }
+        };
+    }
+    let reg_ex = options.reg_ex.as_ref().unwrap();
+    check_chars(reg_ex);
+    match compile_nfa(reg_ex, options.construction.as_deref()) {
+        Ok(nfa) => nfa,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// For applying --and, --or, --not, and --reverse to the transition table
+/// - Input: The regex the table came from, the transition table, and the options
+/// - Output: The combined regex (written with &, |, and ~, or empty once reversed) and transition table
+fn combine(pattern: String, table: Vec<Vec<String>>, options: &Options) -> (String, Vec<Vec<String>>) {
+    let mut pattern = pattern;
+    let mut dfa = Dfa::from_table(&pattern, &table);
+    if options.and.is_none() && options.or.is_none() && !options.not && !options.reverse {
+        return (pattern, table); //nothing to do, keep the table as parse_regex built it
+    }
+    if let Some(other) = &options.and {
+        dfa = dfa.product(&Dfa::from_table(other, &build_table(other, options)), |a, b| a && b);
+        pattern = format!("({})&({})", pattern, other);
+    }
+    if let Some(other) = &options.or {
+        dfa = dfa.product(&Dfa::from_table(other, &build_table(other, options)), |a, b| a || b);
+        pattern = format!("({})|({})", pattern, other);
+    }
+    if options.not {
+        dfa = dfa.complement();
+        pattern = format!("(\\w|\\d| )*~({})", pattern);
+    }
+    if options.reverse {
+        dfa = Nfa::from_dfa(&dfa).reverse().to_dfa("");
+        pattern = String::new(); //there is no regex written for the reverse, to-regex can make one
+    }
+    (pattern, dfa.to_table())
+}
+
+/// For the options a command builds its automata with
+/// parse_regex gets too many patterns wrong to answer questions about them, so every command builds from the
+/// syntax tree with Thompson's construction unless another construction was picked
+/// - Input: The options from the command line
+/// - Output: The same options, with a construction always picked
+fn tree_options(options: &Options) -> Options {
+    let mut build = options.clone();
+    build.construction = build.construction.or(Some("thompson".to_string()));
+    build
+}
+
+/// For the equiv command, checks whether two regexes accept exactly the same strings
+/// Runs both automata side by side and searches for a shortest string only one of them accepts
+/// - Input: The two regular expressions, and the options saying how to build them
+/// - Output: None, the answer is printed to stdout
+fn equiv(reg_ex_1: &str, reg_ex_2: &str, options: &Options) {
+    let first = Dfa::from_table(reg_ex_1, &build_table(reg_ex_1, options));
+    let second = Dfa::from_table(reg_ex_2, &build_table(reg_ex_2, options));
+    match first.product(&second, |a, b| a != b).shortest_accepted() {
+        None => println!("Equivalent"),
+        Some(witness) => {
+            let (yes, no) = if first.accepts(&witness) {(reg_ex_1, reg_ex_2)} else {(reg_ex_2, reg_ex_1)};
+            println!("Not equivalent: \"{}\" is accepted by {} but not by {}", witness, yes, no);
         }
-        else if char == '+'{
-            scanned.push('+');
+    }
+}
+
+/// For the subset command, checks whether every string the first regex accepts is also accepted by the second
+/// Runs the first automaton beside the complement of the second and searches for a shortest string both accept
+/// - Input: The two regular expressions, and the options saying how to build them
+/// - Output: None, the answer is printed to stdout
+fn subset(reg_ex_1: &str, reg_ex_2: &str, options: &Options) {
+    let first = Dfa::from_table(reg_ex_1, &build_table(reg_ex_1, options));
+    let second = Dfa::from_table(reg_ex_2, &build_table(reg_ex_2, options));
+    match first.product(&second.complement(), |a, b| a && b).shortest_accepted() {
+        None => println!("Subset: every string accepted by {} is accepted by {}", reg_ex_1, reg_ex_2),
+        Some(witness) => println!("Not a subset: \"{}\" is accepted by {} but not by {}", witness, reg_ex_1, reg_ex_2),
+    }
+}
+
+/// For reading a whole file, prints an error and exits if it can't be read
+/// - Input: Path of the file
+/// - Output: The contents of the file
+fn read_file(path: &str) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(_) => {
+            eprintln!("Unable to read file {}", path);
+            std::process::exit(1);
         }
-        else if char == '|'{
-            scanned.push('|');
+    }
+}
+
+/// For reading a whole file as bytes, prints an error and exits if it can't be read
+/// - Input: Path of the file
+/// - Output: The bytes of the file
+fn read_binary_file(path: &str) -> Vec<u8> {
+    match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            eprintln!("Unable to read file {}", path);
+            std::process::exit(1);
         }
     }
-    if paren_count != 0 {
-        //not every parenthasis closes, error
-        writeln!(std::io::stderr(), "Invalid Input").unwrap();
+}
+
+/// For writing a whole file, prints an error and exits if it can't be written
+/// - Input: Path of the file and the contents to write
+/// - Output: None
+fn write_file(path: &str, contents: &str) {
+    if std::fs::write(path, contents).is_err() {
+        eprintln!("Unable to write file {}", path);
         std::process::exit(1);
     }
-    scanned
 }
 
+/// For writing a whole file of bytes, prints an error and exits if it can't be written
+/// - Input: Path of the file and the bytes to write
+/// - Output: None
+fn write_binary_file(path: &str, contents: &[u8]) {
+    if std::fs::write(path, contents).is_err() {
+        eprintln!("Unable to write file {}", path);
+        std::process::exit(1);
+    }
+}
+
+
 /// For parsing the scanned regex input and translating it to a DFA
 /// - Input: Vector containing the scanned regex
 /// - Output: Vector of vectors containing the transition diagram
@@ -155,6 +930,7 @@ fn scan_regex(reg: &str) -> Vec<char>{
 ///     - '*' -> *
 ///     - '+' -> +
 ///     - | -> |
+#[allow(clippy::explicit_write)]
 fn parse_regex(reg: Vec<char>) -> Vec<Vec<String>>{
     let invalid_start_symbols = ['*', '+', ')', '|']; //Close parens/brackets, stars, plus, and bar are invalid start symbols
     if invalid_start_symbols.contains(&reg[0]) {
@@ -291,6 +1067,7 @@ fn parse_regex(reg: Vec<char>) -> Vec<Vec<String>>{
 /// A helper for parse_regex
 /// - Input: The current character and the next character
 /// - Output: Boolean value, true if the next character is valid and false if not
+#[allow(clippy::needless_bool)]
 fn invalid_next(first: char, next: &char) -> bool {
     // A { or ( cannot be followed by a *, +, or |
     if first == '(' {
@@ -319,6 +1096,7 @@ fn invalid_next(first: char, next: &char) -> bool {
 /// A function to add a single input character to the transition table
 /// - Input: A character that must be input to reach a state, the current state as a mut value, the transition table, and vector of accept states
 /// - Output: None, the mut parameters are changed as needed
+#[allow(clippy::explicit_counter_loop, clippy::vec_init_then_push)]
 fn add(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>>, accept: &mut Vec<String>) {
     let next_state = *state + 1;
     let alpha = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z'];
@@ -440,6 +1218,7 @@ fn add(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>>, accept: &mu
 /// A function route a specific symbol to a specific state in the transition table
 /// - Input: A character that must be input to reach a state, the current state as a mut value, the transition table, vector of accept states, and a specified next state
 /// - Output: None, the mut parameters are changed as needed
+#[allow(clippy::explicit_counter_loop, clippy::vec_init_then_push)]
 fn add_to(symbol:&char, state: u32, table: &mut Vec<Vec<String>>, accept: &mut Vec<String>, to_state: u32) {
     let alpha = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z'];
     let num = ['0','1','2','3','4','5','6','7','8','9'];
@@ -533,6 +1312,7 @@ fn add_to(symbol:&char, state: u32, table: &mut Vec<Vec<String>>, accept: &mut V
 /// Doesn't reset the transition table
 /// - Input: A character that must be input to reach a state, the current state as a mut value, the transition table, and vector of accept states
 /// - Output: None, the mut parameters are changed as needed
+#[allow(clippy::explicit_counter_loop)]
 fn add_or(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>>, _accept: &mut Vec<String>) {
     let next_state = *state + 1;
     let alpha = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z'];
@@ -581,6 +1361,7 @@ fn add_or(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>>, _accept:
 /// A function to add a starred input character to the transition table
 /// - Input: Char that is starred, current state as a mut value, the transition table, and vector of accept states
 /// - Output: None, the mut parameters are changed as needed
+#[allow(clippy::explicit_counter_loop, clippy::ptr_arg)]
 fn star(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>> , accept: &mut Vec<String>){
     let alpha = ['a','b','c','d','e','f','g','h','i','j','k','l','m','n','o','p','q','r','s','t','u','v','w','x','y','z'];
     let num = ['0','1','2','3','4','5','6','7','8','9'];
@@ -675,6 +1456,7 @@ fn plus(symbol: &char, state: &mut u32, table: &mut Vec<Vec<String>>,  accept: &
 /// A function to add an or statement to the transition table
 /// - Input: Starting index, regex, current state as a mut value, the transition table, character for marking specific symbols before the |, and vector of accept states
 /// - Output: New index to jump to (the index after the last char of the or statement)
+#[allow(clippy::explicit_write, clippy::needless_return, clippy::unnecessary_cast)]
 fn or(index: usize, bookmark: &mut u32, regex: &Vec<char>, state: &mut u32, table: &mut Vec<Vec<String>>, special: char,  accept: &mut Vec<String>) -> usize {
     let left_or = regex[index];
     let mut index_jump = 2;
@@ -1019,6 +1801,7 @@ fn or(index: usize, bookmark: &mut u32, regex: &Vec<char>, state: &mut u32, tabl
 /// For example: a|b|c
 /// - Input: Starting index, regex, current state as a mut value, the transition table, character for marking specific symbols before the |, and vector of accept states
 /// - Output: New index to jump to (the index after the last char of the or statement) 
+#[allow(clippy::explicit_write, clippy::unnecessary_cast)]
 fn stacked_or(index: usize, bookmark: &mut u32, regex: &Vec<char>, state: &mut u32, table: &mut Vec<Vec<String>>,  accept: &mut Vec<String>) -> usize{
     //Set up working vars
     let mut index_jump = 0;
@@ -1266,6 +2049,7 @@ fn stacked_or(index: usize, bookmark: &mut u32, regex: &Vec<char>, state: &mut u
 /// A function to add a plain parenthetical statement to the transition table (the parens can be seen as useless in this situation ex: a(bc)d )
 /// - Input: Starting index, ending index of the paren statement, regex, current state as a mut, mut transition table, and the accept states as a mut
 /// - Output: None, the mut values are changed as needed
+#[allow(clippy::doc_lazy_continuation, clippy::if_same_then_else)]
 fn paren_add(index: usize, bookmark: &mut u32, p_index: usize, regex: &Vec<char>, state: &mut u32, table: &mut Vec<Vec<String>>, accept: &mut Vec<String>){
     let mut i = index;
     let mut special = 'N';
@@ -1309,6 +2093,7 @@ fn paren_add(index: usize, bookmark: &mut u32, p_index: usize, regex: &Vec<char>
 /// A function to add a starred parenthetical statement to the transition table
 /// - Input: Starting index, ending index of the paren statement, regex, current state as a mut, mut transition table, and the accept states as a mut
 /// - Output: None, the mut values are changed as needed
+#[allow(clippy::too_many_arguments, clippy::if_same_then_else, clippy::unnecessary_cast)]
 fn paren_star_plus(index: usize, bookmark: &mut u32, p_index: usize, regex: &Vec<char>, state: &mut u32, table: &mut Vec<Vec<String>>, accept: &mut Vec<String>, special: &str){
     let mut i = index;
     let mut sp = 'N'; //for or statements, serves as the special for that input
@@ -1506,6 +2291,7 @@ fn paren_star_plus(index: usize, bookmark: &mut u32, p_index: usize, regex: &Vec
 /// A function to add an or expression that contains a parenthetical statent to the transition table
 /// - Input: Starting index, regex, current state as a mut value, the transition table, character for marking specific symbols before the |, and vector of accept states
 /// - Output: New index to jump to (the index after the last char of the or statement)
+#[allow(clippy::needless_late_init, clippy::unnecessary_cast)]
 fn paren_or(index: usize, bookmark: &mut u32, p_index: usize, regex: &Vec<char>, state: &mut u32, table: &mut Vec<Vec<String>>, accept: &mut Vec<String>) -> usize{
     let state_holder = *state;
     let end_state;
@@ -1601,9 +2387,9 @@ fn new_table_row() -> Vec::<String> {
 }
 
 /// For reading input from stdin and printing accept or reject for each line
-/// - Input: Transition table
+/// - Input: The matcher, which says whether a line of symbols in SIGMA is accepted (such as check_string on the transition table)
 /// - Output: An accept or reject output followed by the string printed to stderr
-fn process_input(table: Vec<Vec<String>>) {
+fn process_input(mut accepts: impl FnMut(&str) -> bool) {
     let mut stderr = std::io::stderr();
     let stdin = stdin();
     'outer: for line in stdin.lock().lines() {
@@ -1619,7 +2405,7 @@ fn process_input(table: Vec<Vec<String>>) {
         }
         
         //if string chars are valid, make sure it matches the regex
-        if check_string(&string, &table){ //Check to see if it matches the regex here
+        if accepts(&string){ //Check to see if it matches the regex here
             writeln!(&mut stderr, "Accept {}", &string).unwrap();
         }else {
             writeln!(&mut stderr, "Reject {}", &string).unwrap();
@@ -1628,39 +2414,154 @@ fn process_input(table: Vec<Vec<String>>) {
 }
 
 
-/// For navigating the transition table and seeing if strings are valid
-/// - Input: String and transition table
-/// - Output: Boolean, true if string is valid, false if not
-fn check_string(input: &str, table: &Vec<Vec<String>>) -> bool{
-    let mut curr_state = 0;
-    let len = table.len()-1;
-    for char in input.chars() {
-        if curr_state > len-1 {
-            return false //something went wrong
+/// For the set command, checks each stdin line against every regex in one pass
+/// The regexes are numbered from 0 in the order given, those from --patterns after those on the command line
+/// - Input: The options with the regexes
+/// - Output: The numbered regexes printed to stdout, then "Matched" and the numbers of the regexes each line matches
+///   (or "none") and the line, printed to stderr
+fn match_set(options: &Options) {
+    let mut patterns = options.patterns.clone();
+    if let Some(path) = &options.patterns_file {
+        patterns.extend(read_file(path).lines().filter(|line| !line.is_empty()).map(|line| line.to_string()));
+    }
+    let mut dfas = Vec::new();
+    for (i, pattern) in patterns.iter().enumerate() {
+        println!("{}: {}", i, pattern);
+        dfas.push(Dfa::from_table(pattern, &build_table(pattern, options)));
+    }
+    let set = match regex_set::RegexSet::new(&dfas, options.state_limit.unwrap_or(STATE_LIMIT)) {
+        Ok(set) => set,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
         }
-        else if table[curr_state][SIGMA.iter().position(|&x| x==char ).unwrap()] == " " {
-            return false //there is no transition for this input from this state
+    };
+
+    let stdin = stdin();
+    for line in stdin.lock().lines() {
+        let string = line.unwrap();
+        let matched: Vec<String> = set.matches(&string).iter().map(|i| i.to_string()).collect();
+        if matched.is_empty() {
+            eprintln!("Matched none {}", string);
         }
         else {
-            curr_state = table[curr_state][SIGMA.iter().position(|&x| x==char ).unwrap()].parse::<u32>().unwrap() as usize;
+            eprintln!("Matched {} {}", matched.join(","), string);
         }
     }
-    for state in &table[len] {
-        if state == &curr_state.to_string() {return true} //valid end state reached!
+}
+
+/// For the lex command, splits stdin into tokens using the rules file
+/// - Input: The options with the rules file
+/// - Output: (NAME, "lexeme", line, column) for every token printed to stdout, and an error for every
+///   character no rule matches printed to stderr
+fn lex(options: &Options) {
+    let path = options.rules.as_ref().unwrap();
+    let rules = match lexer::parse_rules(&read_file(path)) {
+        Ok(rules) => rules,
+        Err(message) => {
+            eprintln!("Invalid rules in {}: {}", path, message);
+            std::process::exit(1);
+        }
+    };
+    let mut dfas = Vec::new();
+    for rule in &rules {
+        match compile(&rule.regex, options.construction.as_deref(), options.state_limit.unwrap_or(STATE_LIMIT)) {
+            Ok(dfa) => dfas.push(dfa),
+            Err(message) => {
+                eprintln!("Invalid rules in {}: line {} ({}): {}", path, rule.line, rule.name, message);
+                std::process::exit(1);
+            }
+        }
+    }
+    let lexer = match lexer::Lexer::new(&dfas, options.state_limit.unwrap_or(STATE_LIMIT)) {
+        Ok(lexer) => lexer,
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(1);
+        }
+    };
+
+    let stdin = stdin();
+    for (i, line) in stdin.lock().lines().enumerate() {
+        for token in lexer.tokenize(&line.unwrap(), i + 1) {
+            match token.rule {
+                Some(rule) => println!("({}, {:?}, {}, {})", rules[rule].name, token.lexeme, token.line, token.column),
+                None => eprintln!("No rule matches {:?} at line {}, column {}", token.lexeme, token.line, token.column),
+            }
+        }
+    }
+}
+
+/// For the repl command, runs each stdin line as a command or a string to check until :quit or the end of input
+/// The prompt is only shown when stdin is a terminal, so piped input gives just the results
+/// - Input: The options, with the regex to start with if one was given
+/// - Output: The result of every line printed to stdout
+fn repl(options: &Options) {
+    let table = options.table.clone().unwrap_or_else(|| "ascii".to_string());
+    let mut session = repl::Repl::new(options.construction.clone(), options.state_limit.unwrap_or(STATE_LIMIT), table);
+    if let Some(reg_ex) = &options.reg_ex {
+        print!("{}", session.run_line(&format!(":regex {}", reg_ex)).unwrap());
+    }
+
+    let stdin = stdin();
+    let prompt = stdin.is_terminal();
+    let mut stdout = std::io::stdout();
+    if prompt {
+        println!("Type :help for the commands");
+    }
+    let mut lines = stdin.lock().lines();
+    loop {
+        if prompt {
+            write!(&mut stdout, "> ").unwrap();
+            stdout.flush().unwrap();
+        }
+        let line = match lines.next() {
+            Some(line) => line.unwrap(),
+            None => break,
+        };
+        match session.run_line(&line) {
+            Some(output) => write!(&mut stdout, "{}", output).unwrap(),
+            None => break,
+        }
+    }
+}
+
+/// For reading input from stdin and printing where each line matches
+/// - Input: The SpanFinder for the automaton
+/// - Output: "Match start-end text" for every leftmost-longest match, or "No match" and the line, printed to stderr
+fn process_spans(finder: &spans::SpanFinder) {
+    let stdin = stdin();
+    for line in stdin.lock().lines() {
+        let string = line.unwrap();
+        let chars: Vec<char> = string.chars().collect();
+        let found = finder.spans(&string);
+        if found.is_empty() {
+            eprintln!("No match {}", string);
+        }
+        for (start, end) in found {
+            eprintln!("Match {}-{} {}", start, end, chars[start..end].iter().collect::<String>());
+        }
     }
-    false //end state not reached
 }
 
 /// For printing the transition table as a state diagram to stdout.txt
 /// - Input: Transition table
 /// - Output: None
-fn print_state_diagram(table: &Vec<Vec<String>>){
+fn print_state_diagram(table: &[Vec<String>]){
     let mut output = File::create("stdout.txt").expect("Unable to create file");
+    output.write_all(state_diagram(table).as_bytes()).expect("Unable to write to file");
+}
+
+/// For writing the transition table as a state diagram, in the Graphviz layout print_state_diagram saves
+/// - Input: Transition table
+/// - Output: String holding the diagram
+fn state_diagram(table: &[Vec<String>]) -> String {
+    let mut output = String::new();
     //opening lines
-    output.write(b"diagraph {\n\n\tnode [shape=point]; start;\n").expect("Unable to write to file");
+    output.push_str("diagraph {\n\n\tnode [shape=point]; start;\n");
 
     //insert end states for the double circle label
-    output.write(b"\tnode [shape=doublecircle]; ").expect("Unable to write to file");
+    output.push_str("\tnode [shape=doublecircle]; ");
     let mut marker = 0;
     for state in &table[table.len()-1] { //loop through the accept state row
         if state == "X" {
@@ -1671,15 +2572,15 @@ fn print_state_diagram(table: &Vec<Vec<String>>){
                 marker += 1;
             }
             else {
-                output.write(b", ").expect("Unable to write to file");
+                output.push_str(", ");
             }
-            output.write(state.as_bytes()).expect("Unable to write to file");
+            output.push_str(state);
         }
     }
-    output.write(b";\n").expect("Unable to write to file");
+    output.push_str(";\n");
 
     //transition to next section of file
-    output.write(b"\tnode [shape=circle];\n\n\tstart -> 0;\n").expect("Unable to write to file");
+    output.push_str("\tnode [shape=circle];\n\n\tstart -> 0;\n");
 
     //translate state diagram to transitions on a graph
     let mut row_num = 0;
@@ -1692,33 +2593,26 @@ fn print_state_diagram(table: &Vec<Vec<String>>){
         }
         for transition in row {
             if transition != " " {
-                output.write(b"\t").expect("Unable to write to file");
-                output.write(row_num.to_string().as_bytes()).expect("Unable to write to file");
-                output.write(b" -> ").expect("Unable to write to file");
-                output.write(table[row_num][i].as_bytes()).expect("Unable to write to file");
-                output.write(b" [label=\"").expect("Unable to write to file");
-                output.write(SIGMA[i].to_string().as_bytes()).expect("Unable to write to file");
-                output.write(b"\"];\n").expect("Unable to write to file");
+                output.push('\t');
+                output.push_str(&row_num.to_string());
+                output.push_str(" -> ");
+                output.push_str(&table[row_num][i]);
+                output.push_str(" [label=\"");
+                output.push(SIGMA[i]);
+                output.push_str("\"];\n");
             }
             i += 1;
         }
         row_num += 1;
     }
 
-    //end and close file
-    output.write(b"\n}").expect("Unable to write to file");
-}
-
-#[test]
-fn test_scan_regex(){
-    let mut solution = vec!['a','*','b','|','(','c','d','e',')','|','(', 'e','f','g',')'];
-    assert_eq!(scan_regex("a*b|{cde}|(efg)"), solution);
-
-    solution = vec!['!', '@', 'a', 'b', 'z', '8', '+'];
-    assert_eq!(scan_regex("\\w\\dabz8+"), solution);
+    //end the diagram
+    output.push_str("\n}");
+    output
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison)]
 fn test_invalid_next(){
     assert_eq!(invalid_next('(', &'*'), false);
     assert_eq!(invalid_next('|', &'b'), true);
@@ -1727,6 +2621,7 @@ fn test_invalid_next(){
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn test_parse_regex(){
     let scanned_regex = vec!['a','*','b'];
     let mut table = Vec::<Vec::<String>>::new();
@@ -1740,19 +2635,104 @@ fn test_parse_regex(){
 }
 
 #[test]
-fn test_check_string(){
-    let mut table = Vec::<Vec::<String>>::new();
-    let mut row = vec![String::from("0"), String::from("1"), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" ")]; 
-    table.push(row);
-    row = vec![String::from(" "), String::from(String::from(" ")), String::from(String::from(" ")), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" "), String::from(" ")];
-    table.push(row);
-    row = vec![String::from("X"), String::from("1")];
-    table.push(row);
+fn test_parse_options(){
+    let args = vec!["a*b".to_string(), "--export-json".to_string(), "out.json".to_string()];
+    let options = parse_options(&args).unwrap();
+    assert_eq!(options.reg_ex, Some("a*b".to_string()));
+    assert_eq!(options.export_json, Some("out.json".to_string()));
+
+    let args = vec!["--import-json".to_string(), "in.json".to_string()];
+    assert_eq!(parse_options(&args).unwrap().import_json, Some("in.json".to_string()));
+    let args = vec!["--import-jff".to_string(), "in.jff".to_string(), "--import-json".to_string(), "in.json".to_string()];
+    assert_eq!(parse_options(&args), None); //only one automaton can be loaded
+    let args: Vec<String> = ["--import-bin", "in.dfa", "--export-json", "out.json"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args).unwrap().import_bin, Some("in.dfa".to_string()));
+    let args: Vec<String> = ["--import-bin", "in.dfa", "--import-jff", "in.jff"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args), None);
+    let args: Vec<String> = ["a*b", "--cache-dir", "cache", "--export-bin", "out.dfa"].iter().map(|a| a.to_string()).collect();
+    let options = parse_options(&args).unwrap();
+    assert_eq!(options.cache_dir, Some("cache".to_string()));
+    assert_eq!(options.export_bin, Some("out.dfa".to_string()));
+    let args = vec!["a".to_string(), "--format".to_string(), "mermaid".to_string()];
+    assert_eq!(parse_options(&args).unwrap().format, Some("mermaid".to_string()));
+    assert_eq!(parse_options(&["a".to_string(), "--format".to_string(), "svg".to_string()]), None);
+    assert_eq!(parse_options(&["a".to_string(), "--table".to_string(), "html".to_string()]), None);
+
+    let args = vec!["equiv".to_string(), "a*".to_string(), "a+".to_string()];
+    let options = parse_options(&args).unwrap();
+    assert_eq!(options.command, Some("equiv".to_string()));
+    assert_eq!((options.reg_ex, options.reg_ex_2), (Some("a*".to_string()), Some("a+".to_string())));
+    assert_eq!(parse_options(&["equiv".to_string(), "a*".to_string()]), None);
+
+    let args = vec!["--not".to_string(), "a*".to_string(), "--and".to_string(), "\\w+".to_string()];
+    let options = parse_options(&args).unwrap();
+    assert!(options.not);
+    assert_eq!(options.command, None);
+    assert_eq!((options.reg_ex, options.and), (Some("a*".to_string()), Some("\\w+".to_string())));
+
+    let args = vec!["gen".to_string(), "--max-len".to_string(), "3".to_string(), "a*".to_string()];
+    let options = parse_options(&args).unwrap();
+    assert_eq!((options.command, options.max_len), (Some("gen".to_string()), Some(3)));
+    assert_eq!(parse_options(&["gen".to_string(), "a*".to_string()]), None); //no --max-len
+
+    let args: Vec<String> = ["sample", "--len", "4", "--seed", "9", "--near-miss", "\\d+"].iter().map(|a| a.to_string()).collect();
+    let options = parse_options(&args).unwrap();
+    assert_eq!((options.len, options.count, options.seed, options.near_miss), (Some(4), None, Some(9), true));
+    assert_eq!(parse_options(&["sample".to_string(), "a*".to_string()]), None); //no --len
+    assert_eq!(parse_options(&["analyze".to_string(), "a*".to_string()]).unwrap().command, Some("analyze".to_string()));
+    let args: Vec<String> = ["to-regex", "--import-json", "a.json"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args).unwrap().command, Some("to-regex".to_string()));
+    let args: Vec<String> = ["equiv", "--construction", "derivative", "a", "b"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args).unwrap().construction, Some("derivative".to_string()));
+    assert_eq!(parse_options(&["--construction".to_string(), "direct".to_string(), "a".to_string()]), None);
+    let args: Vec<String> = ["--engine", "nfa", "--state-limit", "50", "a*"].iter().map(|a| a.to_string()).collect();
+    let options = parse_options(&args).unwrap();
+    assert_eq!((options.engine, options.state_limit), (Some("nfa".to_string()), Some(50)));
+    let args: Vec<String> = ["gen", "--engine", "nfa", "--max-len", "2", "a*"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args), None); //gen needs a table
+    let args: Vec<String> = ["set", "a*", "\\d", "--patterns", "more.txt", "b"].iter().map(|a| a.to_string()).collect();
+    let options = parse_options(&args).unwrap();
+    assert_eq!((options.patterns, options.patterns_file), (vec!["a*".to_string(), "\\d".to_string(), "b".to_string()], Some("more.txt".to_string())));
+    assert_eq!(parse_options(&["set".to_string()]), None);
+    let options = parse_options(&["lex".to_string(), "--rules".to_string(), "rules.txt".to_string()]).unwrap();
+    assert_eq!(options.rules, Some("rules.txt".to_string()));
+    assert_eq!(parse_options(&["lex".to_string(), "a".to_string()]), None);
+    assert_eq!(parse_options(&["repl".to_string()]).unwrap().reg_ex, None);
+    assert_eq!(parse_options(&["repl".to_string(), "a*".to_string()]).unwrap().reg_ex, Some("a*".to_string()));
+    assert_eq!(parse_options(&["repl".to_string(), "a".to_string(), "b".to_string()]), None);
+    let args: Vec<String> = ["codegen", "--lang", "rust", "--name", "is_id", "\\w+"].iter().map(|a| a.to_string()).collect();
+    let options = parse_options(&args).unwrap();
+    assert_eq!((options.lang, options.name), (Some("rust".to_string()), Some("is_id".to_string())));
+    assert_eq!(parse_options(&["codegen".to_string(), "a".to_string()]), None); //no --lang
+    assert_eq!(parse_options(&["--name".to_string(), "9x".to_string(), "a".to_string()]), None);
+    for (lang, name) in [("rust", "fn"), ("rust", "match"), ("c", "int"), ("c", "return"), ("js", "function"), ("js", "class")] {
+        let args: Vec<String> = ["codegen", "--lang", lang, "--name", name, "a"].iter().map(|a| a.to_string()).collect();
+        assert_eq!(parse_options(&args), None);
+    }
+    let args: Vec<String> = ["codegen", "--name", "match", "--lang", "c", "a"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args).unwrap().name, Some("match".to_string())); //only reserved in Rust
+    let options = parse_options(&["--reverse".to_string(), "--spans".to_string(), "ab".to_string()]).unwrap();
+    assert!(options.reverse && options.spans);
+    let args: Vec<String> = ["--engine", "lazy", "--cache-size", "64", "a*"].iter().map(|a| a.to_string()).collect();
+    assert_eq!(parse_options(&args).unwrap().cache_size, Some(64));
+    let options = parse_options(&["--simplify".to_string(), "(a*)*".to_string()]).unwrap();
+    assert!(options.simplify && options.reg_ex == Some("(a*)*".to_string()));
+    assert_eq!(parse_options(&["a".to_string(), "--max-len".to_string(), "x".to_string()]), None);
+
+    assert_eq!(parse_options(&[]), None); //no regex
+    assert_eq!(parse_options(&["a".to_string(), "b".to_string()]), None); //two regexes
+    assert_eq!(parse_options(&["a".to_string(), "--export-json".to_string()]), None); //missing file name
+}
 
-    assert_eq!(check_string("aaaab", &table), true);
-    assert_eq!(check_string("b", &table), true);
-    assert_eq!(check_string("bbbbb", &table), false);
-    assert_eq!(check_string("", &table), false);
+#[test]
+fn test_can_simulate(){
+    let options = |args: &[&str]| parse_options(&args.iter().map(|a| a.to_string()).collect::<Vec<String>>()).unwrap();
+    assert!(can_simulate(&options(&["--construction", "thompson", "--state-limit", "1", "ab"])));
+    //the simulator would print Accept or Reject for the regex as written, so these have to stop at the limit instead
+    assert!(!can_simulate(&options(&["--construction", "thompson", "--state-limit", "1", "--spans", "ab"])));
+    assert!(!can_simulate(&options(&["--construction", "thompson", "--state-limit", "1", "--reverse", "ab"])));
+    assert!(!can_simulate(&options(&["--state-limit", "1", "--not", "ab"])));
+    assert!(!can_simulate(&options(&["--construction", "derivative", "ab"])));
 }
 
 #[test]
@@ -0,0 +1,178 @@
+//! # Nondeterministic Finite Automaton
+//! Used for automata that can have more than one move per symbol, or moves on no symbol at all (epsilon moves),
+//! such as ones drawn in JFLAP. Subset construction turns them into a Dfa.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::dfa::Dfa;
+use crate::SIGMA;
+
+/// An NFA with epsilon moves
+/// - states: Number of states, they are numbered 0..states
+/// - start: The state the automaton begins in
+/// - accepting: Sorted list of the accepting states
+/// - transitions: (from, symbol, to) for every move, a symbol of None is an epsilon move
+#[derive(Debug, Clone, PartialEq)]
+pub struct Nfa {
+    pub states: usize,
+    pub start: usize,
+    pub accepting: Vec<usize>,
+    pub transitions: Vec<(usize, Option<char>, usize)>,
+}
+
+impl Nfa {
+    /// For viewing a Dfa as an NFA (every DFA already is one)
+    /// - Input: The Dfa
+    /// - Output: An Nfa with the same states and moves
+    pub fn from_dfa(dfa: &Dfa) -> Nfa {
+        let mut transitions = Vec::new();
+        for (from, row) in dfa.transitions.iter().enumerate() {
+            for (i, target) in row.iter().enumerate() {
+                if let Some(to) = target {
+                    transitions.push((from, Some(dfa.alphabet[i]), *to));
+                }
+            }
+        }
+        Nfa {
+            states: dfa.transitions.len(),
+            start: dfa.start,
+            accepting: dfa.accepting.clone(),
+            transitions,
+        }
+    }
+
+    /// For reversing the automaton, so it accepts exactly the accepted strings written backwards
+    /// Every move is turned around, a new start state has epsilon moves to the old accepting states,
+    /// and the old start state is the only accepting state
+    /// - Input: None
+    /// - Output: The reversed Nfa (subset construction makes it deterministic again)
+    pub fn reverse(&self) -> Nfa {
+        let start = self.states;
+        let mut transitions: Vec<(usize, Option<char>, usize)> = self.transitions.iter().map(|(from, symbol, to)| (*to, *symbol, *from)).collect();
+        for st in &self.accepting {
+            transitions.push((start, None, *st));
+        }
+        Nfa { states: self.states + 1, start, accepting: vec![self.start], transitions }
+    }
+
+    /// For finding every state reachable from a set of states using only epsilon moves
+    /// - Input: The starting set of states
+    /// - Output: Sorted list of the states in the closure
+    fn epsilon_closure(&self, set: &[usize]) -> Vec<usize> {
+        let mut in_closure = vec![false; self.states];
+        let mut stack = set.to_vec();
+        for st in set {
+            in_closure[*st] = true;
+        }
+        while let Some(st) = stack.pop() {
+            for (from, symbol, to) in &self.transitions {
+                if *from == st && symbol.is_none() && !in_closure[*to] {
+                    in_closure[*to] = true;
+                    stack.push(*to);
+                }
+            }
+        }
+        (0..self.states).filter(|st| in_closure[*st]).collect()
+    }
+
+    /// For turning the NFA into a Dfa over SIGMA with subset construction
+    /// Sets of NFA states become single DFA states, the empty set is left out so missing moves reject like they do in the table
+    /// - Input: The regex the automaton came from (may be empty)
+    /// - Output: The equivalent Dfa, with 0 as its start state
+    pub fn to_dfa(&self, pattern: &str) -> Dfa {
+        self.to_dfa_limited(pattern, usize::MAX).unwrap()
+    }
+
+    /// For subset construction that gives up once the Dfa has too many states
+    /// - Input: The regex the automaton came from (may be empty), and the most Dfa states to make
+    /// - Output: The equivalent Dfa, or None if it would need more states than the limit
+    pub fn to_dfa_limited(&self, pattern: &str, limit: usize) -> Option<Dfa> {
+        let mut sets = vec![self.epsilon_closure(&[self.start])];
+        let mut index_of = HashMap::new();
+        index_of.insert(sets[0].clone(), 0);
+        let mut transitions = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front() {
+            let mut row = vec![None; SIGMA.len()];
+            for (i, sym) in SIGMA.iter().enumerate() {
+                //every state reachable on this symbol, then everything reachable from those with epsilon moves
+                let mut moved = Vec::new();
+                for (from, symbol, to) in &self.transitions {
+                    if *symbol == Some(*sym) && sets[current].contains(from) && !moved.contains(to) {
+                        moved.push(*to);
+                    }
+                }
+                if moved.is_empty() {
+                    continue;
+                }
+                let target = self.epsilon_closure(&moved);
+                let next = match index_of.get(&target) {
+                    Some(next) => *next,
+                    None => {
+                        if sets.len() == limit {
+                            return None;
+                        }
+                        sets.push(target.clone());
+                        index_of.insert(target, sets.len() - 1);
+                        queue.push_back(sets.len() - 1);
+                        sets.len() - 1
+                    }
+                };
+                row[i] = Some(next);
+            }
+            transitions.push(row); //states leave the queue in the order they were numbered
+        }
+
+        let mut accepting = Vec::new();
+        for (i, set) in sets.iter().enumerate() {
+            if set.iter().any(|st| self.accepting.contains(st)) {
+                accepting.push(i);
+            }
+        }
+
+        Some(Dfa {
+            pattern: pattern.to_string(),
+            alphabet: SIGMA.to_vec(),
+            transitions,
+            start: 0,
+            accepting,
+        })
+    }
+}
+
+#[test]
+fn test_to_dfa(){
+    //a(b|epsilon)a, as an NFA with an epsilon move from 1 to 2
+    let nfa = Nfa {
+        states: 4,
+        start: 0,
+        accepting: vec![3],
+        transitions: vec![(0, Some('a'), 1), (1, Some('b'), 2), (1, None, 2), (2, Some('a'), 3)],
+    };
+    let table = nfa.to_dfa("").to_table();
+    assert!(crate::check_string("aba", &table));
+    assert!(crate::check_string("aa", &table));
+    assert!(!crate::check_string("ab", &table));
+    assert!(!crate::check_string("abba", &table));
+
+    //the Dfa has 4 states (start, after a, after ab or a, accepting), so a limit of 3 gives up
+    assert_eq!(nfa.to_dfa_limited("", 4).map(|dfa| dfa.transitions.len()), Some(4));
+    assert_eq!(nfa.to_dfa_limited("", 3), None);
+}
+
+#[test]
+fn test_reverse(){
+    //ab*c reversed is cb*a
+    let nfa = Nfa {
+        states: 3,
+        start: 0,
+        accepting: vec![2],
+        transitions: vec![(0, Some('a'), 1), (1, Some('b'), 1), (1, Some('c'), 2)],
+    };
+    let reversed = nfa.reverse().to_dfa("");
+    assert!(reversed.accepts("ca") && reversed.accepts("cbba"));
+    assert!(!reversed.accepts("abc") && !reversed.accepts("cb"));
+}
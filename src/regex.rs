@@ -0,0 +1,275 @@
+//! # Regular expression syntax tree
+//! parse_regex builds the transition table straight from the scanned regex.
+//! This is the regex as a tree instead, so it can be built into automata in other ways
+//! and support operators that parse_regex can't, like '&' (intersection) and '~' (difference).
+//!
+//! Grammar, over the output of scan_regex (\w is '!', \d is '@', and both kinds of brackets are '(' and ')'):
+//!     B -> B&E|B~E|E
+//!     E -> C|E    //The '|' character is part of the actual definition here
+//!     E -> C
+//!     C -> SC|S
+//!     S -> P*|P+|P
+//!     P -> (B)|L
+//!     L -> \w|\d|A
+
+use crate::SIGMA;
+
+/// A parsed regular expression
+/// - Symbols: Any one of the listed symbols, in SIGMA order (a single character, \w, or \d)
+/// - Concat: Each part in order
+/// - Union: Any one of the options
+/// - Star: Zero or more repeats
+/// - Plus: One or more repeats
+/// - And: Strings matched by both sides
+/// - Minus: Strings matched by the left side but not the right
+/// - Epsilon: Only the empty string (never parsed, comes from building a tree out of an automaton)
+/// - Empty: No strings at all (never parsed either)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Regex {
+    Symbols(Vec<char>),
+    Concat(Vec<Regex>),
+    Union(Vec<Regex>),
+    Star(Box<Regex>),
+    Plus(Box<Regex>),
+    And(Box<Regex>, Box<Regex>),
+    Minus(Box<Regex>, Box<Regex>),
+    Epsilon,
+    Empty,
+}
+
+/// For parsing a scanned regex into a Regex tree
+/// - Input: Vector containing the scanned regex
+/// - Output: The tree, or a message saying where the regex is invalid
+pub fn parse(reg: &[char]) -> Result<Regex, String> {
+    let mut parser = Parser { reg, pos: 0 };
+    let tree = parser.parse_bool()?;
+    if parser.pos < reg.len() {
+        return Err(format!("unexpected '{}' at position {}", reg[parser.pos], parser.pos));
+    }
+    Ok(tree)
+}
+
+/// For checking whether a tree matches the empty string
+/// - Input: The tree
+/// - Output: True if the empty string is matched
+pub fn nullable(regex: &Regex) -> bool {
+    match regex {
+        Regex::Symbols(_) | Regex::Empty => false,
+        Regex::Plus(inner) => nullable(inner),
+        Regex::Concat(parts) => parts.iter().all(nullable),
+        Regex::Union(options) => options.iter().any(nullable),
+        Regex::Star(_) | Regex::Epsilon => true,
+        Regex::And(left, right) => nullable(left) && nullable(right),
+        Regex::Minus(left, right) => nullable(left) && !nullable(right),
+    }
+}
+
+/// For writing a tree back out in the tool's regex syntax, with only the brackets that are needed
+/// The syntax has no way to write the empty string or no strings, so those are written as (a~a)* and a~a
+/// - Input: The tree
+/// - Output: String holding the regex
+pub fn to_pattern(regex: &Regex) -> String {
+    write_pattern(regex, 0)
+}
+
+/// For writing part of a tree, bracketed if it binds looser than the place it is written in
+/// Binding levels: 0 for & and ~, 1 for |, 2 for concatenation, 3 for * and +, 4 for single symbols
+fn write_pattern(regex: &Regex, level: usize) -> String {
+    let (own, text) = match regex {
+        Regex::Symbols(symbols) => {
+            let pieces = symbol_pieces(symbols);
+            if pieces.len() == 1 && pieces[0].starts_with('(') {
+                (0, pieces[0][1..pieces[0].len() - 1].to_string()) //a lone (\\w~x) only needs its brackets sometimes
+            }
+            else {
+                (if pieces.len() == 1 {4} else {1}, pieces.join("|"))
+            }
+        }
+        Regex::Concat(parts) => (2, parts.iter().map(|p| write_pattern(p, 3)).collect()),
+        Regex::Union(options) => (1, options.iter().map(|o| write_pattern(o, 1)).collect::<Vec<String>>().join("|")),
+        Regex::Star(inner) => (3, format!("{}*", write_pattern(inner, 4))),
+        Regex::Plus(inner) => (3, format!("{}+", write_pattern(inner, 4))),
+        Regex::And(left, right) => (0, format!("{}&{}", write_pattern(left, 0), write_pattern(right, 1))),
+        Regex::Minus(left, right) => (0, format!("{}~{}", write_pattern(left, 0), write_pattern(right, 1))),
+        Regex::Epsilon => (3, "(a~a)*".to_string()),
+        Regex::Empty => (0, "a~a".to_string()),
+    };
+    if own < level {
+        format!("({})", text)
+    }
+    else {
+        text
+    }
+}
+
+/// For writing a set of symbols as \\w, \\d, and single characters
+/// Most but not all of the letters (or digits) are written as what is left out, like (\\w~(i|o))
+fn symbol_pieces(symbols: &[char]) -> Vec<String> {
+    let mut pieces = Vec::new();
+    for (class, all, short) in [("\\w", 26, 14), ("\\d", 10, 6)] {
+        let in_class = |c: &char| if class == "\\w" {c.is_ascii_lowercase()} else {c.is_ascii_digit()};
+        let count = symbols.iter().filter(|c| in_class(c)).count();
+        if count == all {
+            pieces.push(class.to_string());
+        }
+        else if count >= short {
+            let missing: Vec<String> = SIGMA.iter().filter(|c| in_class(c) && !symbols.contains(c)).map(|c| c.to_string()).collect();
+            if missing.len() == 1 {
+                pieces.push(format!("({}~{})", class, missing[0]));
+            }
+            else {
+                pieces.push(format!("({}~({}))", class, missing.join("|")));
+            }
+        }
+        else {
+            pieces.extend(symbols.iter().filter(|c| in_class(c)).map(|c| c.to_string()));
+        }
+    }
+    if symbols.contains(&' ') {
+        pieces.push(" ".to_string());
+    }
+    pieces
+}
+
+/// A recursive descent parser following the grammar above, one function per rule
+struct Parser<'a> {
+    reg: &'a [char],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.reg.get(self.pos).copied()
+    }
+
+    /// B -> B&E|B~E|E, both operators group to the left
+    fn parse_bool(&mut self) -> Result<Regex, String> {
+        let mut left = self.parse_union()?;
+        while let Some(op) = self.peek() {
+            if op != '&' && op != '~' {
+                break;
+            }
+            self.pos += 1;
+            let right = self.parse_union()?;
+            left = if op == '&' {
+                Regex::And(Box::new(left), Box::new(right))
+            }
+            else {
+                Regex::Minus(Box::new(left), Box::new(right))
+            };
+        }
+        Ok(left)
+    }
+
+    /// E -> C|E|C
+    fn parse_union(&mut self) -> Result<Regex, String> {
+        let mut options = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.pos += 1;
+            options.push(self.parse_concat()?);
+        }
+        if options.len() == 1 {
+            Ok(options.pop().unwrap())
+        }
+        else {
+            Ok(Regex::Union(options))
+        }
+    }
+
+    /// C -> SC|S
+    fn parse_concat(&mut self) -> Result<Regex, String> {
+        let mut parts = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' || c == '&' || c == '~' {
+                break;
+            }
+            parts.push(self.parse_repeat()?);
+        }
+        match parts.len() {
+            0 => Err(format!("missing expression at position {}", self.pos)),
+            1 => Ok(parts.pop().unwrap()),
+            _ => Ok(Regex::Concat(parts)),
+        }
+    }
+
+    /// S -> P*|P+|P, a repeat can't be repeated again (the same rule as invalid_next)
+    fn parse_repeat(&mut self) -> Result<Regex, String> {
+        let inner = self.parse_primary()?;
+        let tree = match self.peek() {
+            Some('*') => Regex::Star(Box::new(inner)),
+            Some('+') => Regex::Plus(Box::new(inner)),
+            _ => return Ok(inner),
+        };
+        self.pos += 1;
+        if self.peek() == Some('*') || self.peek() == Some('+') {
+            return Err(format!("repeated '{}' at position {}", self.reg[self.pos], self.pos));
+        }
+        Ok(tree)
+    }
+
+    /// P -> (B)|L and L -> \w|\d|A
+    fn parse_primary(&mut self) -> Result<Regex, String> {
+        let c = self.peek().ok_or("unexpected end of regex")?;
+        self.pos += 1;
+        if c == '(' {
+            let inner = self.parse_bool()?;
+            if self.peek() != Some(')') {
+                return Err(format!("missing ')' at position {}", self.pos));
+            }
+            self.pos += 1;
+            Ok(inner)
+        }
+        else if c == '!' {
+            Ok(Regex::Symbols(SIGMA.iter().copied().filter(|x| x.is_ascii_lowercase()).collect()))
+        }
+        else if c == '@' {
+            Ok(Regex::Symbols(SIGMA.iter().copied().filter(|x| x.is_ascii_digit()).collect()))
+        }
+        else if SIGMA.contains(&c) {
+            Ok(Regex::Symbols(vec![c]))
+        }
+        else {
+            Err(format!("unexpected '{}' at position {}", c, self.pos - 1))
+        }
+    }
+}
+
+#[test]
+fn test_parse(){
+    let a = Regex::Symbols(vec!['a']);
+    let b = Regex::Symbols(vec!['b']);
+    assert_eq!(parse(&['a', 'b', '*']), Ok(Regex::Concat(vec![a.clone(), Regex::Star(Box::new(b.clone()))])));
+    assert_eq!(parse(&['(', 'a', '|', 'b', ')', '+']), Ok(Regex::Plus(Box::new(Regex::Union(vec![a.clone(), b.clone()])))));
+
+    //& and ~ bind looser than |
+    let tree = parse(&['a', '|', 'b', '~', 'b']).unwrap();
+    assert_eq!(tree, Regex::Minus(Box::new(Regex::Union(vec![a.clone(), b.clone()])), Box::new(b.clone())));
+
+    assert!(parse(&['a', '|']).is_err());
+    assert!(parse(&['(', ')']).is_err());
+    assert!(parse(&['a', '*', '+']).is_err());
+    assert!(parse(&['&', 'a']).is_err());
+}
+
+#[test]
+fn test_to_pattern(){
+    let a = Regex::Symbols(vec!['a']);
+    let b = Regex::Symbols(vec!['b']);
+    let letters: Vec<char> = SIGMA.iter().copied().filter(|x| x.is_ascii_lowercase()).collect();
+    assert_eq!(to_pattern(&Regex::Star(Box::new(Regex::Concat(vec![a.clone(), b.clone()])))), "(ab)*");
+    assert_eq!(to_pattern(&Regex::Concat(vec![Regex::Union(vec![a.clone(), b.clone()]), Regex::Plus(Box::new(Regex::Symbols(letters.clone())))])), "(a|b)\\w+");
+    assert_eq!(to_pattern(&Regex::Star(Box::new(Regex::Symbols(vec!['a', 'b', '0'])))), "(a|b|0)*");
+
+    let mut word = letters;
+    word.push(' ');
+    assert_eq!(to_pattern(&Regex::Symbols(word.clone())), "\\w| ");
+    word.retain(|c| *c != 'i' && *c != ' ');
+    assert_eq!(to_pattern(&Regex::Symbols(word.clone())), "\\w~i");
+    assert_eq!(to_pattern(&Regex::Plus(Box::new(Regex::Symbols(word)))), "(\\w~i)+");
+    assert_eq!(to_pattern(&Regex::Union(vec![Regex::Epsilon, a.clone()])), "(a~a)*|a");
+
+    //the printed pattern parses back to the same tree
+    let tree = parse(&['(', 'a', '|', 'b', 'a', ')', '+', '~', 'a', 'b']).unwrap();
+    assert_eq!(to_pattern(&tree), "(a|ba)+~ab");
+    assert!(!nullable(&tree) && nullable(&Regex::Star(Box::new(a))));
+}
@@ -0,0 +1,100 @@
+//! # Regex sets
+//! Checks a line against many regexes in one pass. The automata for all of them are run side by side,
+//! like Dfa::product but with any number of automata, so each state of the combined Dfa is a tuple holding
+//! where every one of them is. Each state is tagged with the regexes that accept there.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+use crate::dfa::Dfa;
+use crate::SIGMA;
+
+/// A Dfa for many regexes at once
+/// - dfa: The combined automaton, accepting wherever at least one of the regexes accepts
+/// - tags: For every state, the indices of the regexes that accept there, in order
+pub struct RegexSet {
+    pub dfa: Dfa,
+    pub tags: Vec<Vec<usize>>,
+}
+
+impl RegexSet {
+    /// For combining the automata of several regexes
+    /// - Input: The automata, in the order their indices should be reported, and the most states the combined automaton may have
+    /// - Output: The RegexSet, or a message saying it needs too many states
+    pub fn new(dfas: &[Dfa], state_limit: usize) -> Result<RegexSet, String> {
+        //None stands for a dead automaton, the tuple with every one dead is left out
+        let mut tuples: Vec<Vec<Option<usize>>> = vec![dfas.iter().map(|d| Some(d.start)).collect()];
+        let mut index_of: HashMap<Vec<Option<usize>>, usize> = HashMap::new();
+        index_of.insert(tuples[0].clone(), 0);
+        let mut transitions = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+
+        while let Some(current) = queue.pop_front() {
+            let mut row = vec![None; SIGMA.len()];
+            for (i, sym) in SIGMA.iter().enumerate() {
+                let next: Vec<Option<usize>> = tuples[current].iter().zip(dfas).map(|(st, d)| st.and_then(|st| d.step(st, *sym))).collect();
+                if next.iter().all(|st| st.is_none()) {
+                    continue;
+                }
+                let index = match index_of.get(&next) {
+                    Some(index) => *index,
+                    None => {
+                        if tuples.len() == state_limit {
+                            return Err(format!("Too many states: combining the regexes passed {}", state_limit));
+                        }
+                        tuples.push(next.clone());
+                        index_of.insert(next, tuples.len() - 1);
+                        queue.push_back(tuples.len() - 1);
+                        tuples.len() - 1
+                    }
+                };
+                row[i] = Some(index);
+            }
+            transitions.push(row); //tuples leave the queue in the order they were numbered
+        }
+
+        let tags: Vec<Vec<usize>> = tuples.iter()
+            .map(|tuple| (0..dfas.len()).filter(|i| tuple[*i].is_some_and(|st| dfas[*i].is_accepting(st))).collect())
+            .collect();
+        let dfa = Dfa {
+            pattern: String::new(),
+            alphabet: SIGMA.to_vec(),
+            transitions,
+            start: 0,
+            accepting: (0..tuples.len()).filter(|st| !tags[*st].is_empty()).collect(),
+        };
+        Ok(RegexSet { dfa, tags })
+    }
+
+    /// For finding which regexes accept a whole string
+    /// - Input: The string
+    /// - Output: The indices of the regexes that accept it, in order (empty if none do)
+    pub fn matches(&self, input: &str) -> Vec<usize> {
+        let mut state = self.dfa.start;
+        for c in input.chars() {
+            match self.dfa.step(state, c) {
+                Some(next) => state = next,
+                None => return Vec::new(),
+            }
+        }
+        self.tags[state].clone()
+    }
+}
+
+#[test]
+fn test_regex_set(){
+    let dfas: Vec<Dfa> = ["\\d+", "\\w+", "\\w\\d*", "(\\w|\\d)*"].iter().map(|p| crate::build(p)).collect();
+    let set = RegexSet::new(&dfas, crate::STATE_LIMIT).unwrap();
+    assert_eq!(set.matches("123"), vec![0, 3]);
+    assert_eq!(set.matches("abc"), vec![1, 3]);
+    assert_eq!(set.matches("a12"), vec![2, 3]);
+    assert_eq!(set.matches("a"), vec![1, 2, 3]);
+    assert_eq!(set.matches(""), vec![3]);
+    assert_eq!(set.matches("a b"), Vec::<usize>::new());
+
+    //the product of (a|b)*a(a|b) and (a|b)*b(a|b) needs more than 4 states
+    let dfas: Vec<Dfa> = ["(a|b)*a(a|b)", "(a|b)*b(a|b)"].iter().map(|p| crate::build(p)).collect();
+    assert!(RegexSet::new(&dfas, 4).is_err_and(|message| message.starts_with("Too many states")));
+    assert!(RegexSet::new(&dfas, 100).is_ok());
+}
@@ -0,0 +1,134 @@
+//! # Interactive mode
+//! Reads one line at a time so a pattern can be changed and tried again without running the program again.
+//! Lines starting with ':' are commands, any other line is checked against the current automaton.
+//! Regexes are built from the syntax tree (Thompson's construction unless --construction picks another),
+//! so a mistake in one is reported and the session carries on with the automaton it had.
+
+use crate::compile;
+use crate::dfa::Dfa;
+use crate::state_diagram;
+use crate::table;
+
+const HELP: &str = "\
+:regex RegEx   build the automaton for RegEx
+:dot           print the state diagram, as it is written to stdout.txt
+:table         print the transition table (in the --table layout, ascii by default)
+:min           replace the automaton with the one with the fewest states
+:gen N         list every accepted string up to length N
+:trace String  show the states String moves through
+:help          list the commands
+:quit          leave (so does the end of input)
+Any other line is checked against the automaton and printed with Accept or Reject
+";
+
+/// The state of a session
+/// - dfa: The current automaton (None until a regex is given)
+/// - construction: How to build automata from regexes, one of CONSTRUCTIONS (None for thompson)
+/// - state_limit: Most states subset construction may make
+/// - table: Layout for :table, one of TABLES
+pub struct Repl {
+    dfa: Option<Dfa>,
+    construction: Option<String>,
+    state_limit: usize,
+    table: String,
+}
+
+impl Repl {
+    /// For starting a session with no automaton
+    /// - Input: The construction, state limit, and table layout from the command line
+    /// - Output: The Repl
+    pub fn new(construction: Option<String>, state_limit: usize, table: String) -> Repl {
+        Repl { dfa: None, construction, state_limit, table }
+    }
+
+    /// For running one line of input
+    /// - Input: The line
+    /// - Output: What to print for it, or None to end the session
+    pub fn run_line(&mut self, line: &str) -> Option<String> {
+        if !line.starts_with(':') {
+            return Some(match &self.dfa {
+                Some(dfa) if dfa.accepts(line) => format!("Accept {}\n", line),
+                Some(_) => format!("Reject {}\n", line),
+                None => "No regex yet, give one with :regex RegEx\n".to_string(),
+            });
+        }
+
+        //the argument is everything after the first space, it may hold spaces of its own
+        let (command, argument) = match line.find(' ') {
+            Some(i) => (&line[..i], &line[i + 1..]),
+            None => (line, ""),
+        };
+        match command {
+            ":quit" => return None,
+            ":help" => return Some(HELP.to_string()),
+            ":regex" => {
+                return Some(match compile(argument, self.construction.as_deref(), self.state_limit) {
+                    Ok(dfa) => {
+                        let report = format!("{} states\n", dfa.transitions.len());
+                        self.dfa = Some(dfa);
+                        report
+                    }
+                    Err(message) => format!("{}\n", message),
+                });
+            }
+            ":dot" | ":table" | ":min" | ":gen" | ":trace" => {}
+            _ => return Some(format!("Unknown command {}, :help lists them\n", command)),
+        }
+
+        let dfa = match &self.dfa {
+            Some(dfa) => dfa,
+            None => return Some("No regex yet, give one with :regex RegEx\n".to_string()),
+        };
+        Some(match command {
+            ":dot" => format!("{}\n", state_diagram(&dfa.to_table())),
+            ":table" => table::format_table(dfa, &self.table),
+            ":min" => {
+                let min = dfa.minimize();
+                let report = format!("{} states, down from {}\n", min.transitions.len(), dfa.transitions.len());
+                self.dfa = Some(min);
+                report
+            }
+            ":gen" => match argument.trim().parse() {
+                Ok(max_len) => dfa.enumerate(max_len, 1000).iter().map(|string| format!("{}\n", string)).collect(),
+                Err(_) => "Usage: :gen N\n".to_string(),
+            },
+            _ => trace(dfa, argument),
+        })
+    }
+}
+
+/// For showing the states a string moves through, stopping where there is no move
+/// - Input: The Dfa, and the string
+/// - Output: The path, like "0 -a-> 1 -b-> 2 Accept"
+fn trace(dfa: &Dfa, input: &str) -> String {
+    let mut state = dfa.start;
+    let mut out = state.to_string();
+    for c in input.chars() {
+        match dfa.step(state, c) {
+            Some(next) => {
+                out.push_str(&format!(" -{}-> {}", if c == ' ' {"space".to_string()} else {c.to_string()}, next));
+                state = next;
+            }
+            None => return format!("{}, no move on {:?} Reject\n", out, c),
+        }
+    }
+    format!("{} {}\n", out, if dfa.is_accepting(state) {"Accept"} else {"Reject"})
+}
+
+#[test]
+fn test_repl(){
+    let mut repl = Repl::new(None, 10000, "ascii".to_string());
+    assert_eq!(repl.run_line("ab"), Some("No regex yet, give one with :regex RegEx\n".to_string()));
+    assert!(repl.run_line(":regex a(b").unwrap().starts_with("Invalid regex"));
+    assert!(repl.run_line(":regex (a|b)*b").unwrap().ends_with(" states\n"));
+    assert_eq!(repl.run_line("ab"), Some("Accept ab\n".to_string()));
+    assert_eq!(repl.run_line("ba"), Some("Reject ba\n".to_string()));
+    assert_eq!(repl.run_line(":min"), Some("2 states, down from 3\n".to_string()));
+    assert_eq!(repl.run_line(":gen 2"), Some("b\nab\nbb\n".to_string()));
+    assert_eq!(repl.run_line(":trace ab"), Some("0 -a-> 0 -b-> 1 Accept\n".to_string()));
+    assert_eq!(repl.run_line(":trace bc"), Some("0 -b-> 1, no move on 'c' Reject\n".to_string()));
+    assert!(repl.run_line(":dot").unwrap().contains("0 -> 1 [label=\"b\"];"));
+    assert_eq!(repl.run_line(":gen"), Some("Usage: :gen N\n".to_string()));
+    assert!(repl.run_line(":undo").unwrap().starts_with("Unknown command :undo"));
+    assert_eq!(repl.run_line(":quit"), None);
+}
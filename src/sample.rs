@@ -0,0 +1,153 @@
+//! # Random test strings
+//! Picks accepted strings of one length uniformly at random, by counting how many accepted strings
+//! follow each move (Dfa::count_accepted) and choosing moves in proportion to those counts.
+//! Near misses are accepted strings with one symbol changed, added, or removed so they are rejected,
+//! which is where a pattern is most likely to be wrong.
+//! There are no dependencies, so the random numbers come from a small xorshift generator.
+
+use crate::dfa::Dfa;
+
+/// A xorshift64* random number generator, plenty for picking test strings
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// For making a generator, the same seed always gives the same numbers
+    /// - Input: The seed
+    /// - Output: The generator
+    pub fn new(seed: u64) -> Rng {
+        //the state can't be zero, and close seeds shouldn't give close numbers
+        Rng { state: (seed ^ 0x9E37_79B9_7F4A_7C15).max(1) }
+    }
+
+    /// For making a generator seeded from the clock
+    pub fn from_time() -> Rng {
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        Rng::new(nanos as u64)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// For picking a number below the bound (the bound must not be 0), every number equally likely
+    /// Draws past the last whole multiple of the bound are thrown away, or the low numbers would come up more often
+    pub fn below(&mut self, bound: u128) -> u128 {
+        //2^128 % bound, the draws left over past the last multiple
+        let extra = (u128::MAX % bound + 1) % bound;
+        loop {
+            let wide = (self.next() as u128) << 64 | self.next() as u128;
+            if wide <= u128::MAX - extra {
+                return wide % bound;
+            }
+        }
+    }
+}
+
+/// For picking an accepted string of the given length, every such string equally likely
+/// Counts that reached u128::MAX are no longer exact, so the choice would not be uniform and is refused
+/// - Input: The Dfa, counts from Dfa::count_accepted (at least len + 1 rows), the length, and the generator
+/// - Output: The string, or a message if no string of that length is accepted or there are too many to count
+pub fn sample_accepted(dfa: &Dfa, counts: &[Vec<u128>], len: usize, rng: &mut Rng) -> Result<String, String> {
+    let mut state = dfa.start;
+    //every count used below adds into this one, so if none of them stopped at the cap neither did this
+    match counts[len][state] {
+        0 => return Err(format!("No strings of length {} are accepted", len)),
+        u128::MAX => return Err(format!("Too many strings of length {} are accepted to pick one uniformly", len)),
+        _ => {}
+    }
+    let mut string = String::new();
+    for remaining in (0..len).rev() {
+        //pick a move with a chance in proportion to the accepted strings after it
+        let mut pick = rng.below(counts[remaining + 1][state]);
+        for (i, target) in dfa.transitions[state].iter().enumerate() {
+            if let Some(next) = target {
+                if pick < counts[remaining][*next] {
+                    string.push(dfa.alphabet[i]);
+                    state = *next;
+                    break;
+                }
+                pick -= counts[remaining][*next];
+            }
+        }
+    }
+    Ok(string)
+}
+
+/// For turning an accepted string into a rejected one with a single edit
+/// Tries random edits (change, add, or remove one symbol) until one is rejected
+/// - Input: The Dfa, the accepted string, and the generator
+/// - Output: The rejected string, or None if no edit tried was rejected
+pub fn near_miss(dfa: &Dfa, string: &str, rng: &mut Rng) -> Option<String> {
+    let chars: Vec<char> = string.chars().collect();
+    for _ in 0..100 {
+        let mut edited = chars.clone();
+        let symbol = dfa.alphabet[rng.below(dfa.alphabet.len() as u128) as usize];
+        match rng.below(3) {
+            0 if !edited.is_empty() => {
+                let at = rng.below(edited.len() as u128) as usize;
+                edited[at] = symbol;
+            }
+            1 if !edited.is_empty() => {
+                edited.remove(rng.below(edited.len() as u128) as usize);
+            }
+            _ => {
+                let at = rng.below(edited.len() as u128 + 1) as usize;
+                edited.insert(at, symbol);
+            }
+        }
+        let edited: String = edited.into_iter().collect();
+        if !dfa.accepts(&edited) {
+            return Some(edited);
+        }
+    }
+    None
+}
+
+#[test]
+fn test_sample(){
+    use crate::SIGMA;
+
+    //(a|b)b*
+    let mut first = vec![" ".to_string(); SIGMA.len()];
+    first[0] = "1".to_string();
+    first[1] = "1".to_string();
+    let mut second = vec![" ".to_string(); SIGMA.len()];
+    second[1] = "1".to_string();
+    let dfa = Dfa::from_table("", &[first, second, vec!["X".to_string(), "1".to_string()]]);
+    let counts = dfa.count_accepted(3);
+
+    let mut rng = Rng::new(7);
+    let mut seen = Vec::new();
+    for _ in 0..50 {
+        let string = sample_accepted(&dfa, &counts, 3, &mut rng).unwrap();
+        assert!(dfa.accepts(&string) && string.len() == 3);
+        if !seen.contains(&string) {
+            seen.push(string);
+        }
+    }
+    seen.sort();
+    assert_eq!(seen, vec!["abb", "bbb"]);
+    assert!(sample_accepted(&dfa, &counts, 0, &mut rng).unwrap_err().starts_with("No strings"));
+
+    //37^30 strings of length 30 is past what a u128 holds
+    let every = Dfa::from_table("", &[vec!["0".to_string(); SIGMA.len()], vec!["X".to_string(), "0".to_string()]]);
+    assert!(sample_accepted(&every, &every.count_accepted(30), 30, &mut rng).unwrap_err().starts_with("Too many"));
+    assert!(sample_accepted(&every, &every.count_accepted(20), 20, &mut rng).is_ok());
+
+    let miss = near_miss(&dfa, "abb", &mut rng).unwrap();
+    assert!(!dfa.accepts(&miss));
+
+    //3 * 2^126 doesn't divide 2^128, taking % alone would make the first third come up half the time
+    let bound = 3u128 << 126;
+    let low = (0..3000).filter(|_| rng.below(bound) < 1u128 << 126).count();
+    assert!((900..1100).contains(&low));
+
+    //the same seed gives the same strings
+    let (mut one, mut two) = (Rng::new(3), Rng::new(3));
+    assert_eq!(sample_accepted(&dfa, &counts, 3, &mut one), sample_accepted(&dfa, &counts, 3, &mut two));
+}
@@ -0,0 +1,313 @@
+//! # Regex simplification
+//! Building a regex out of an automaton stacks up pieces like (a~a)*a, a|a, and (b*)*.
+//! These constructors apply Kleene algebra identities as each piece is made, so trees stay small:
+//!     ε·X = X·ε = X        ∅·X = X·∅ = ∅        X|∅ = X        X|X = X
+//!     X*X = XX* = X+       X*X* = X*            (X*)* = (X+)* = X* = X+ when X matches ε
+//!     ε|X = X when X matches ε, ε|X+ = X*, and symbol sets joined by | become one set (a|b|\w is \w)
+//!     XY|XZ = X(Y|Z) and YX|ZX = (Y|Z)X, so b|a+b becomes (ε|a+)b and then a*b
+//!     (X*|Y)* = (X|Y)*     (X*Y*)* = (X|Y)*      X&X = X              X~X = ∅
+//!     X&∅ = ∅~X = ∅        X~∅ = X               ε&X and ε~X are ε or ∅ by whether X matches ε
+//! Options of a union are kept sorted, so the same language written in a different order simplifies the same way.
+//! simplify rebuilds a whole parsed tree with these, from the leaves up.
+
+use crate::regex::{nullable, to_pattern, Regex};
+use crate::SIGMA;
+
+/// For joining options with |
+/// - Input: The options
+/// - Output: The simplified union
+pub fn union(options: Vec<Regex>) -> Regex {
+    //flatten nested unions and drop options that match nothing
+    let mut flat = Vec::new();
+    for option in options {
+        match option {
+            Regex::Union(inner) => flat.extend(inner),
+            Regex::Empty => {}
+            other => flat.push(other),
+        }
+    }
+
+    //join all the symbol sets into the place of the first one, and drop repeats
+    let mut joined: Vec<Regex> = Vec::new();
+    let mut symbols: Option<usize> = None;
+    for option in flat {
+        if let Regex::Symbols(new) = &option {
+            if let Some(at) = symbols {
+                if let Regex::Symbols(old) = &mut joined[at] {
+                    old.extend(new);
+                    old.sort_by_key(|c| SIGMA.iter().position(|x| x == c));
+                    old.dedup();
+                }
+                continue;
+            }
+            symbols = Some(joined.len());
+        }
+        if !joined.contains(&option) {
+            joined.push(option);
+        }
+    }
+
+    //X is already matched by X* and X+ is already matched by X*
+    let starred: Vec<Regex> = joined.iter().filter_map(|o| if let Regex::Star(x) = o {Some((**x).clone())} else {None}).collect();
+    joined.retain(|o| !starred.contains(o) && !matches!(o, Regex::Plus(x) if starred.contains(x)));
+
+    //ε is already matched by any option that matches it, or turns an X+ into X*
+    if joined.contains(&Regex::Epsilon) {
+        if joined.iter().any(|o| *o != Regex::Epsilon && nullable(o)) {
+            joined.retain(|o| *o != Regex::Epsilon);
+        }
+        else if let Some(at) = joined.iter().position(|o| matches!(o, Regex::Plus(_))) {
+            if let Regex::Plus(x) = joined.remove(at) {
+                joined.insert(at, Regex::Star(x));
+            }
+            joined.retain(|o| *o != Regex::Epsilon);
+        }
+    }
+
+    //pull out a shared start or end from two options, then simplify what is left again
+    let sequence = |o: &Regex| match o {
+        Regex::Concat(parts) => parts.clone(),
+        other => vec![other.clone()],
+    };
+    for i in 0..joined.len() {
+        for j in i + 1..joined.len() {
+            let (first, second) = (sequence(&joined[i]), sequence(&joined[j]));
+            let prefix = first.iter().zip(&second).take_while(|(x, y)| x == y).count();
+            let suffix = first.iter().rev().zip(second.iter().rev()).take_while(|(x, y)| x == y).count();
+            let factored = if prefix > 0 {
+                let rest = union(vec![concat(first[prefix..].to_vec()), concat(second[prefix..].to_vec())]);
+                concat(first[..prefix].iter().cloned().chain(std::iter::once(rest)).collect())
+            }
+            else if suffix > 0 {
+                let rest = union(vec![concat(first[..first.len() - suffix].to_vec()), concat(second[..second.len() - suffix].to_vec())]);
+                concat(std::iter::once(rest).chain(first[first.len() - suffix..].iter().cloned()).collect())
+            }
+            else {
+                continue;
+            };
+            joined.remove(j);
+            joined[i] = factored;
+            return union(joined);
+        }
+    }
+
+    joined.sort_by_cached_key(to_pattern);
+    match joined.len() {
+        0 => Regex::Empty,
+        1 => joined.pop().unwrap(),
+        _ => Regex::Union(joined),
+    }
+}
+
+/// For joining parts one after another
+/// - Input: The parts
+/// - Output: The simplified concatenation
+pub fn concat(parts: Vec<Regex>) -> Regex {
+    let mut flat = Vec::new();
+    for part in parts {
+        match part {
+            Regex::Concat(inner) => flat.extend(inner),
+            Regex::Epsilon => {}
+            Regex::Empty => return Regex::Empty,
+            other => flat.push(other),
+        }
+    }
+
+    //merge a star with a copy of what it repeats on either side (x*x and xx* are x+), and a repeat with another repeat of it
+    let mut i = 0;
+    while i < flat.len() {
+        let (inner, is_plus) = match &flat[i] {
+            Regex::Star(x) => ((**x).clone(), false),
+            Regex::Plus(x) => ((**x).clone(), true),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let copy = match &inner {
+            Regex::Concat(inner_parts) => inner_parts.clone(),
+            other => vec![other.clone()],
+        };
+        let n = copy.len();
+        if !is_plus && i + 1 + n <= flat.len() && flat[i + 1..i + 1 + n] == copy[..] {
+            flat.drain(i + 1..i + 1 + n);
+            flat[i] = plus(inner);
+        }
+        else if !is_plus && i >= n && flat[i - n..i] == copy[..] {
+            flat.drain(i - n..i);
+            i -= n;
+            flat[i] = plus(inner);
+        }
+        else if i + 1 < flat.len() && matches!(&flat[i + 1], Regex::Star(x) | Regex::Plus(x) if **x == inner) {
+            let next_plus = matches!(flat[i + 1], Regex::Plus(_));
+            if is_plus && next_plus {
+                //x+x+ needs at least two copies, so it is xx+
+                let mut merged = copy;
+                merged.push(Regex::Plus(Box::new(inner)));
+                flat.splice(i..i + 2, merged);
+                i += n;
+            }
+            else {
+                flat.remove(i + 1);
+                flat[i] = if is_plus || next_plus {Regex::Plus(Box::new(inner))} else {Regex::Star(Box::new(inner))};
+            }
+        }
+        else {
+            i += 1;
+        }
+    }
+
+    match flat.len() {
+        0 => Regex::Epsilon,
+        1 => flat.pop().unwrap(),
+        _ => Regex::Concat(flat),
+    }
+}
+
+/// For repeating a piece zero or more times
+/// - Input: The piece
+/// - Output: The simplified star
+pub fn star(inner: Regex) -> Regex {
+    match inner {
+        Regex::Empty | Regex::Epsilon => Regex::Epsilon,
+        Regex::Star(x) | Regex::Plus(x) => Regex::Star(x),
+        //repeats inside the options of a repeat add nothing, and neither does ε
+        Regex::Union(options) if options.iter().any(|o| matches!(o, Regex::Epsilon | Regex::Star(_) | Regex::Plus(_))) => {
+            star(union(options.into_iter().map(unrepeat).filter(|o| *o != Regex::Epsilon).collect()))
+        }
+        //a run of starred parts repeated is any mix of them
+        Regex::Concat(parts) if parts.iter().all(|p| matches!(p, Regex::Star(_))) => {
+            star(union(parts.into_iter().map(unrepeat).collect()))
+        }
+        other => Regex::Star(Box::new(other)),
+    }
+}
+
+/// For taking the * or + off a piece, if it has one
+fn unrepeat(regex: Regex) -> Regex {
+    match regex {
+        Regex::Star(x) | Regex::Plus(x) => *x,
+        other => other,
+    }
+}
+
+/// For repeating a piece one or more times
+/// - Input: The piece
+/// - Output: The simplified plus
+pub fn plus(inner: Regex) -> Regex {
+    match inner {
+        Regex::Empty => Regex::Empty,
+        Regex::Epsilon => Regex::Epsilon,
+        Regex::Star(_) | Regex::Plus(_) => inner,
+        other if nullable(&other) => star(other),
+        other => Regex::Plus(Box::new(other)),
+    }
+}
+
+/// For keeping only the strings both sides match
+/// - Input: The two sides
+/// - Output: The simplified intersection
+pub fn and(left: Regex, right: Regex) -> Regex {
+    if left == right {
+        left
+    }
+    else if left == Regex::Empty || right == Regex::Empty {
+        Regex::Empty
+    }
+    else if left == Regex::Epsilon || right == Regex::Epsilon {
+        //ε if the other side matches it, otherwise nothing
+        if nullable(&left) && nullable(&right) {Regex::Epsilon} else {Regex::Empty}
+    }
+    else {
+        Regex::And(Box::new(left), Box::new(right))
+    }
+}
+
+/// For keeping the strings the left side matches but the right doesn't
+/// - Input: The two sides
+/// - Output: The simplified difference
+pub fn minus(left: Regex, right: Regex) -> Regex {
+    if left == right || left == Regex::Empty {
+        Regex::Empty
+    }
+    else if right == Regex::Empty {
+        left
+    }
+    else if left == Regex::Epsilon {
+        if nullable(&right) {Regex::Empty} else {Regex::Epsilon}
+    }
+    else {
+        Regex::Minus(Box::new(left), Box::new(right))
+    }
+}
+
+/// For simplifying a whole tree, rebuilding it from the leaves up with the constructors above
+/// - Input: The tree
+/// - Output: A tree for the same strings, usually smaller
+pub fn simplify(regex: &Regex) -> Regex {
+    match regex {
+        Regex::Symbols(symbols) => Regex::Symbols(symbols.clone()),
+        Regex::Concat(parts) => concat(parts.iter().map(simplify).collect()),
+        Regex::Union(options) => union(options.iter().map(simplify).collect()),
+        Regex::Star(inner) => star(simplify(inner)),
+        Regex::Plus(inner) => plus(simplify(inner)),
+        Regex::And(left, right) => and(simplify(left), simplify(right)),
+        Regex::Minus(left, right) => minus(simplify(left), simplify(right)),
+        Regex::Epsilon => Regex::Epsilon,
+        Regex::Empty => Regex::Empty,
+    }
+}
+
+#[test]
+fn test_constructors(){
+    let a = Regex::Symbols(vec!['a']);
+    let b = Regex::Symbols(vec!['b']);
+    let a_star = Regex::Star(Box::new(a.clone()));
+
+    assert_eq!(union(vec![b.clone(), a.clone(), a.clone(), Regex::Empty]), Regex::Symbols(vec!['a', 'b']));
+    assert_eq!(union(vec![Regex::Epsilon, Regex::Plus(Box::new(a.clone()))]), a_star);
+    assert_eq!(union(vec![a.clone(), a_star.clone()]), a_star);
+    assert_eq!(union(vec![Regex::Epsilon, a_star.clone()]), a_star);
+
+    assert_eq!(concat(vec![Regex::Epsilon, a.clone(), a_star.clone()]), Regex::Plus(Box::new(a.clone())));
+    assert_eq!(concat(vec![a_star.clone(), a_star.clone()]), a_star);
+    assert_eq!(concat(vec![b.clone(), Regex::Empty]), Regex::Empty);
+    let ab = Regex::Concat(vec![a.clone(), b.clone()]);
+    assert_eq!(concat(vec![ab.clone(), Regex::Star(Box::new(ab.clone()))]), Regex::Plus(Box::new(ab)));
+
+    assert_eq!(star(Regex::Star(Box::new(a.clone()))), a_star);
+    assert_eq!(star(Regex::Union(vec![Regex::Epsilon, a.clone()])), a_star);
+    assert_eq!(star(Regex::Empty), Regex::Epsilon);
+    assert_eq!(plus(a_star.clone()), a_star);
+
+    //b|a+b is (ε|a+)b, which is a*b
+    let a_plus_b = Regex::Concat(vec![Regex::Plus(Box::new(a.clone())), b.clone()]);
+    assert_eq!(union(vec![b.clone(), a_plus_b]), Regex::Concat(vec![a_star.clone(), b.clone()]));
+    //ab|ac is a(b|c)
+    let ab = Regex::Concat(vec![a.clone(), b.clone()]);
+    let ac = Regex::Concat(vec![a.clone(), Regex::Symbols(vec!['c'])]);
+    assert_eq!(union(vec![ab, ac]), Regex::Concat(vec![a.clone(), Regex::Symbols(vec!['b', 'c'])]));
+}
+
+#[test]
+fn test_simplify(){
+    let simplified = |pattern: &str| to_pattern(&simplify(&crate::tree(pattern)));
+    assert_eq!(simplified("(a*)*"), "a*");
+    assert_eq!(simplified("a|a"), "a");
+    assert_eq!(simplified("(b)"), "b");
+    assert_eq!(simplified("a*a*"), "a*");
+    assert_eq!(simplified("\\d|0"), "\\d");
+    assert_eq!(simplified("(a*|b+)*"), "(a|b)*");
+    assert_eq!(simplified("(a*b*)*c"), "(a|b)*c");
+    assert_eq!(simplified("ba|ab|ba"), "ab|ba");
+    assert_eq!(simplified("aa*|(b~b)"), "a+");
+    assert_eq!(simplified("(a|b)~(b|a)"), "a~a");
+
+    //a + next to a copy or another + still needs the copy
+    assert_eq!(simplified("aa+"), "aa+");
+    assert_eq!(simplified("a+a"), "a+a");
+    assert_eq!(simplified("a+a+"), "aa+");
+    assert_eq!(simplified("(ab)+(ab)+"), "ab(ab)+");
+    assert_eq!(simplified("a+a+a+"), "aaa+");
+    assert_eq!(simplified("a*a+"), "a+");
+}
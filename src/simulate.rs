@@ -0,0 +1,182 @@
+//! # NFA simulation
+//! Matches strings against an Nfa directly, keeping the set of states it could be in after each symbol,
+//! instead of building a Dfa first. Subset construction can need a Dfa state for every one of those sets,
+//! 2^n for an n state Nfa on patterns like (a|b)*a(a|b)(a|b)(a|b), but a simulation only ever holds one set.
+//! Sets are kept as sparse sets (a list of members plus a lookup by state), so clearing one and
+//! checking membership don't depend on how many states the Nfa has.
+
+use crate::nfa::Nfa;
+
+/// An Nfa arranged for fast simulation
+/// - moves: For every state, its (symbol, target) moves
+/// - epsilon: For every state, the targets of its epsilon moves
+/// - accepting: For every state, whether it accepts
+/// - start: The start state and everything reachable from it with epsilon moves, sorted
+/// - current, next: Sets reused by every step and match, cleared and swapped instead of allocated
+pub struct Simulator {
+    moves: Vec<Vec<(char, usize)>>,
+    epsilon: Vec<Vec<usize>>,
+    accepting: Vec<bool>,
+    start: Vec<usize>,
+    current: SparseSet,
+    next: SparseSet,
+}
+
+/// A set of states with constant time insert, lookup, and clear
+/// - dense: The members in the order they were added
+/// - sparse: For every state, where it would be in dense (only meaningful if dense agrees)
+#[derive(Default)]
+struct SparseSet {
+    dense: Vec<usize>,
+    sparse: Vec<usize>,
+}
+
+impl SparseSet {
+    fn new(size: usize) -> SparseSet {
+        SparseSet { dense: Vec::new(), sparse: vec![0; size] }
+    }
+
+    fn contains(&self, state: usize) -> bool {
+        let at = self.sparse[state];
+        at < self.dense.len() && self.dense[at] == state
+    }
+
+    fn insert(&mut self, state: usize) -> bool {
+        if self.contains(state) {
+            return false;
+        }
+        self.sparse[state] = self.dense.len();
+        self.dense.push(state);
+        true
+    }
+
+    fn clear(&mut self) {
+        self.dense.clear();
+    }
+}
+
+impl Simulator {
+    /// For arranging an Nfa for simulation
+    /// - Input: The Nfa
+    /// - Output: The Simulator
+    pub fn new(nfa: &Nfa) -> Simulator {
+        let mut moves = vec![Vec::new(); nfa.states];
+        let mut epsilon = vec![Vec::new(); nfa.states];
+        for (from, symbol, to) in &nfa.transitions {
+            match symbol {
+                Some(c) => moves[*from].push((*c, *to)),
+                None => epsilon[*from].push(*to),
+            }
+        }
+        let mut accepting = vec![false; nfa.states];
+        for st in &nfa.accepting {
+            accepting[*st] = true;
+        }
+        let current = SparseSet::new(nfa.states);
+        let next = SparseSet::new(nfa.states);
+        let mut simulator = Simulator { moves, epsilon, accepting, start: Vec::new(), current, next };
+        let mut set = SparseSet::new(nfa.states);
+        simulator.add(&mut set, nfa.start);
+        simulator.start = set.dense;
+        simulator.start.sort_unstable();
+        simulator
+    }
+
+    /// For adding a state and everything reachable from it with epsilon moves to a set
+    fn add(&self, set: &mut SparseSet, state: usize) {
+        let mut stack = vec![state];
+        while let Some(st) = stack.pop() {
+            if set.insert(st) {
+                stack.extend(&self.epsilon[st]);
+            }
+        }
+    }
+
+    /// For the states the simulation begins in
+    pub fn start(&self) -> Vec<usize> {
+        self.start.clone()
+    }
+
+    /// For moving a set of states on one symbol
+    /// - Input: The sorted set of states, and the symbol
+    /// - Output: The sorted set of states reachable by the symbol and then epsilon moves (empty if none)
+    pub fn step(&mut self, states: &[usize], c: char) -> Vec<usize> {
+        let mut next = std::mem::take(&mut self.next);
+        next.clear();
+        for st in states {
+            for (symbol, to) in &self.moves[*st] {
+                if *symbol == c {
+                    self.add(&mut next, *to);
+                }
+            }
+        }
+        let mut states = next.dense.clone();
+        states.sort_unstable();
+        self.next = next;
+        states
+    }
+
+    /// For checking whether a set of states accepts
+    pub fn is_accepting(&self, states: &[usize]) -> bool {
+        states.iter().any(|st| self.accepting[*st])
+    }
+
+    /// For checking a string by simulation, reusing the simulator's two sets for the whole string
+    /// - Input: The string
+    /// - Output: True if the Nfa accepts it
+    pub fn accepts(&mut self, input: &str) -> bool {
+        let mut current = std::mem::take(&mut self.current);
+        let mut next = std::mem::take(&mut self.next);
+        current.clear();
+        for st in &self.start {
+            current.insert(*st);
+        }
+        let mut stuck = false;
+        for c in input.chars() {
+            next.clear();
+            for i in 0..current.dense.len() {
+                for (symbol, to) in &self.moves[current.dense[i]] {
+                    if *symbol == c {
+                        self.add(&mut next, *to);
+                    }
+                }
+            }
+            if next.dense.is_empty() {
+                stuck = true;
+                break;
+            }
+            std::mem::swap(&mut current, &mut next);
+        }
+        let accepted = !stuck && current.dense.iter().any(|st| self.accepting[*st]);
+        self.current = current;
+        self.next = next;
+        accepted
+    }
+}
+
+#[test]
+fn test_simulator(){
+    use crate::regex::parse;
+    use crate::thompson::thompson;
+
+    //(a|b)*a(a|b)(a|b): the third symbol from the end is an a
+    let nfa = thompson(&crate::tree("(a|b)*a(a|b)(a|b)"));
+    let mut simulator = Simulator::new(&nfa);
+    assert!(simulator.accepts("abb"));
+    assert!(simulator.accepts("bbbaba"));
+    assert!(!simulator.accepts("bbbbaa"));
+    assert!(!simulator.accepts("ab"));
+    assert!(!simulator.accepts("abc"));
+
+    //stepping by hand gives the same answer
+    let mut states = simulator.start();
+    for c in "babb".chars() {
+        states = simulator.step(&states, c);
+    }
+    assert!(simulator.is_accepting(&states));
+    assert!(simulator.step(&states, 'c').is_empty());
+
+    //ε is accepted when the start state is
+    let mut star = Simulator::new(&thompson(&parse(&['a', '*']).unwrap()));
+    assert!(star.accepts("") && star.accepts("aaa") && !star.accepts("b"));
+}
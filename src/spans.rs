@@ -0,0 +1,98 @@
+//! # Match spans
+//! check_string only says whether a whole line matches. This finds the parts of a line that match,
+//! leftmost-longest like POSIX: the match starting furthest left, as long as it can be, then the next one after it.
+//! Starts are found with the reversed automaton: reading the line backwards with the reverse of R(\w|\d| )*
+//! (the regex followed by anything), it accepts at exactly the positions a match of R starts at.
+//! That is one pass for the whole line, then from each start a forward scan with R finds where the longest match ends.
+
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+use crate::SIGMA;
+
+/// The automata for finding spans
+/// - forward: The automaton for the regex
+/// - backward: The reverse of the regex followed by anything
+pub struct SpanFinder {
+    forward: Dfa,
+    backward: Dfa,
+}
+
+impl SpanFinder {
+    /// For making the reversed automaton for a regex's automaton
+    /// - Input: The Dfa for the regex
+    /// - Output: The SpanFinder
+    pub fn new(dfa: &Dfa) -> SpanFinder {
+        //R followed by anything: every accepting state has an epsilon move to a new state that loops on every symbol
+        let mut nfa = Nfa::from_dfa(dfa);
+        let any = nfa.states;
+        nfa.states += 1;
+        for st in &nfa.accepting {
+            nfa.transitions.push((*st, None, any));
+        }
+        for c in SIGMA.iter() {
+            nfa.transitions.push((any, Some(*c), any));
+        }
+        nfa.accepting = vec![any];
+        SpanFinder { forward: dfa.clone(), backward: nfa.reverse().to_dfa("") }
+    }
+
+    /// For finding the leftmost-longest matches in a line, skipping empty matches
+    /// - Input: The line
+    /// - Output: (start, end) of each match, counted in characters with the end just past the match
+    pub fn spans(&self, line: &str) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = line.chars().collect();
+
+        //read the line backwards, a character outside the alphabet can't be in a match so start over after it
+        let mut starts = vec![false; chars.len()];
+        let mut state = Some(self.backward.start);
+        for i in (0..chars.len()).rev() {
+            state = state.and_then(|st| self.backward.step(st, chars[i]));
+            if state.is_none() {
+                state = Some(self.backward.start);
+                continue;
+            }
+            starts[i] = self.backward.is_accepting(state.unwrap());
+        }
+
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        while let Some(start) = (pos..chars.len()).find(|i| starts[*i]) {
+            //go as far as the automaton can, remembering the last place it accepted
+            let mut state = self.forward.start;
+            let mut end = start;
+            for (i, c) in chars.iter().enumerate().skip(start) {
+                match self.forward.step(state, *c) {
+                    Some(next) => state = next,
+                    None => break,
+                }
+                if self.forward.is_accepting(state) {
+                    end = i + 1;
+                }
+            }
+            if end > start {
+                spans.push((start, end));
+                pos = end;
+            }
+            else {
+                pos = start + 1; //only the empty string matches here
+            }
+        }
+        spans
+    }
+}
+
+#[test]
+fn test_spans(){
+    let finder = |pattern: &str| SpanFinder::new(&crate::build(pattern));
+    assert_eq!(finder("ab").spans("abcxab"), vec![(0, 2), (4, 6)]);
+    assert_eq!(finder("\\d+").spans("on 12 and 345"), vec![(3, 5), (10, 13)]);
+
+    //leftmost first, even though c ends sooner, then longest
+    assert_eq!(finder("abcd|c").spans("abcd"), vec![(0, 4)]);
+    assert_eq!(finder("a|ab|abc").spans("abcab"), vec![(0, 3), (3, 5)]);
+
+    //empty matches are skipped, and characters outside the alphabet split the line
+    assert_eq!(finder("a*").spans("baab"), vec![(1, 3)]);
+    assert_eq!(finder("ab").spans("a.ab"), vec![(2, 4)]);
+    assert_eq!(finder("x").spans("abc"), Vec::<(usize, usize)>::new());
+}
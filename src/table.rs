@@ -0,0 +1,123 @@
+//! # Human readable transition tables
+//! The transition table from parse_regex has a column for every symbol in SIGMA, most of them empty.
+//! These tables only show the symbols that are used, and symbols whose columns are the same are shown as one column (a-z for \w).
+
+use crate::diagram::label;
+use crate::dfa::Dfa;
+
+/// For writing the transition table of a Dfa in a readable layout
+/// The start state is marked with →, accepting states with *, and missing transitions are shown as -
+/// - Input: The Dfa, and the layout: "markdown", "csv", or "ascii" (ascii uses -> instead of →)
+/// - Output: String holding the table
+pub fn format_table(dfa: &Dfa, style: &str) -> String {
+    //group the used symbols by their column, symbols with the same column share one
+    let mut groups: Vec<(Vec<char>, Vec<Option<usize>>)> = Vec::new();
+    for (i, sym) in dfa.alphabet.iter().enumerate() {
+        let column: Vec<Option<usize>> = dfa.transitions.iter().map(|row| row[i]).collect();
+        if column.iter().all(|t| t.is_none()) {
+            continue; //symbol is never used
+        }
+        match groups.iter_mut().find(|(_, c)| *c == column) {
+            Some(group) => group.0.push(*sym),
+            None => groups.push((vec![*sym], column)),
+        }
+    }
+
+    //build every cell first so the ascii layout can size its columns
+    let arrow = if style == "ascii" {"->"} else {"→"};
+    let mut rows = Vec::new();
+    let mut header = vec!["state".to_string()];
+    for (symbols, _) in &groups {
+        header.push(label(symbols));
+    }
+    rows.push(header);
+    for st in 0..dfa.transitions.len() {
+        let mut name = String::new();
+        if st == dfa.start {
+            name.push_str(arrow);
+        }
+        if dfa.accepting.contains(&st) {
+            name.push('*');
+        }
+        name.push_str(&st.to_string());
+        let mut row = vec![name];
+        for (_, column) in &groups {
+            row.push(match column[st] {
+                Some(target) => target.to_string(),
+                None => if style == "csv" {String::new()} else {"-".to_string()},
+            });
+        }
+        rows.push(row);
+    }
+
+    let mut out = String::new();
+    if style == "csv" {
+        for row in &rows {
+            let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+    }
+    else if style == "markdown" {
+        for (i, row) in rows.iter().enumerate() {
+            out.push_str(&format!("| {} |\n", row.join(" | ")));
+            if i == 0 {
+                out.push_str(&format!("|{}\n", "---|".repeat(row.len())));
+            }
+        }
+    }
+    else {
+        let widths: Vec<usize> = (0..rows[0].len()).map(|c| rows.iter().map(|r| r[c].chars().count()).max().unwrap()).collect();
+        let line: String = widths.iter().map(|w| format!("+{}", "-".repeat(w + 2))).collect::<String>() + "+\n";
+        out.push_str(&line);
+        for (i, row) in rows.iter().enumerate() {
+            for (c, cell) in row.iter().enumerate() {
+                out.push_str(&format!("| {:width$} ", cell, width = widths[c]));
+            }
+            out.push_str("|\n");
+            if i == 0 {
+                out.push_str(&line);
+            }
+        }
+        out.push_str(&line);
+    }
+    out
+}
+
+/// For quoting a CSV field if it holds a comma or quote
+fn csv_field(field: &str) -> String {
+    if field.contains(',') || field.contains('"') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    }
+    else {
+        field.to_string()
+    }
+}
+
+#[test]
+fn test_format_table(){
+    //\w\d: letters then a digit
+    let alphabet: Vec<char> = crate::SIGMA.to_vec();
+    let dfa = Dfa {
+        pattern: "\\w\\d".to_string(),
+        alphabet: alphabet.clone(),
+        transitions: vec![
+            alphabet.iter().map(|c| if c.is_ascii_lowercase() {Some(1)} else {None}).collect(),
+            alphabet.iter().map(|c| if c.is_ascii_digit() {Some(2)} else {None}).collect(),
+            vec![None; alphabet.len()],
+        ],
+        start: 0,
+        accepting: vec![2],
+    };
+    assert_eq!(format_table(&dfa, "markdown"), "| state | a-z | 0-9 |\n|---|---|---|\n| →0 | 1 | - |\n| 1 | - | 2 |\n| *2 | - | - |\n");
+    assert_eq!(format_table(&dfa, "csv"), "state,a-z,0-9\n→0,1,\n1,,2\n*2,,\n");
+    assert_eq!(format_table(&dfa, "ascii"), "\
++-------+-----+-----+
+| state | a-z | 0-9 |
++-------+-----+-----+
+| ->0   | 1   | -   |
+| 1     | -   | 2   |
+| *2    | -   | -   |
++-------+-----+-----+
+");
+}
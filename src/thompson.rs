@@ -0,0 +1,131 @@
+//! # Thompson's construction
+//! Builds an Nfa from a Regex tree. Every piece of the tree becomes a small NFA with one start and one end state,
+//! joined to the others by epsilon moves. '&' and '~' have no Thompson piece of their own, so both sides
+//! are built into Dfas, run side by side with Dfa::product, and the result is fitted in as a piece.
+
+use crate::nfa::Nfa;
+use crate::regex::Regex;
+
+/// For building an Nfa from a Regex tree
+/// - Input: The tree
+/// - Output: An Nfa with one accepting state that accepts the same strings
+pub fn thompson(regex: &Regex) -> Nfa {
+    let mut nfa = Nfa { states: 0, start: 0, accepting: Vec::new(), transitions: Vec::new() };
+    let (start, end) = build(regex, &mut nfa);
+    nfa.start = start;
+    nfa.accepting = vec![end];
+    nfa
+}
+
+/// For adding a new state to the Nfa being built
+fn new_state(nfa: &mut Nfa) -> usize {
+    nfa.states += 1;
+    nfa.states - 1
+}
+
+/// For adding the piece for one part of the tree
+/// - Input: The part of the tree, and the Nfa being built
+/// - Output: The start and end states of the new piece
+fn build(regex: &Regex, nfa: &mut Nfa) -> (usize, usize) {
+    match regex {
+        Regex::Symbols(symbols) => {
+            let start = new_state(nfa);
+            let end = new_state(nfa);
+            for c in symbols {
+                nfa.transitions.push((start, Some(*c), end));
+            }
+            (start, end)
+        }
+        Regex::Epsilon | Regex::Empty => {
+            let start = new_state(nfa);
+            let end = new_state(nfa);
+            if let Regex::Epsilon = regex {
+                nfa.transitions.push((start, None, end));
+            }
+            (start, end)
+        }
+        Regex::Concat(parts) => {
+            let (start, mut end) = build(&parts[0], nfa);
+            for part in &parts[1..] {
+                let (next_start, next_end) = build(part, nfa);
+                nfa.transitions.push((end, None, next_start));
+                end = next_end;
+            }
+            (start, end)
+        }
+        Regex::Union(options) => {
+            let start = new_state(nfa);
+            let end = new_state(nfa);
+            for option in options {
+                let (option_start, option_end) = build(option, nfa);
+                nfa.transitions.push((start, None, option_start));
+                nfa.transitions.push((option_end, None, end));
+            }
+            (start, end)
+        }
+        Regex::Star(inner) | Regex::Plus(inner) => {
+            let start = new_state(nfa);
+            let end = new_state(nfa);
+            let (inner_start, inner_end) = build(inner, nfa);
+            nfa.transitions.push((start, None, inner_start));
+            nfa.transitions.push((inner_end, None, inner_start)); //repeat
+            nfa.transitions.push((inner_end, None, end));
+            if let Regex::Star(_) = regex {
+                nfa.transitions.push((start, None, end)); //zero times
+            }
+            (start, end)
+        }
+        Regex::And(left, right) | Regex::Minus(left, right) => {
+            let left = thompson(left).to_dfa("");
+            let right = thompson(right).to_dfa("");
+            let combined = if let Regex::And(_, _) = regex {
+                left.product(&right, |a, b| a && b)
+            }
+            else {
+                left.product(&right, |a, b| a && !b)
+            };
+
+            //copy the combined automaton in, then join its accepting states to one end state
+            let piece = Nfa::from_dfa(&combined);
+            let offset = nfa.states;
+            nfa.states += piece.states;
+            for (from, symbol, to) in piece.transitions {
+                nfa.transitions.push((from + offset, symbol, to + offset));
+            }
+            let end = new_state(nfa);
+            for st in piece.accepting {
+                nfa.transitions.push((st + offset, None, end));
+            }
+            (piece.start + offset, end)
+        }
+    }
+}
+
+#[test]
+fn test_thompson(){
+    use crate::check_string;
+    use crate::regex::parse;
+
+    //(ab|c)*d
+    let tree = parse(&['(', 'a', 'b', '|', 'c', ')', '*', 'd']).unwrap();
+    let table = thompson(&tree).to_dfa("").to_table();
+    assert!(check_string("d", &table));
+    assert!(check_string("abcabd", &table));
+    assert!(!check_string("abc", &table));
+    assert!(!check_string("acd", &table));
+
+    //identifiers that aren't keywords: \w+~(if|in)
+    let tree = parse(&['!', '+', '~', '(', 'i', 'f', '|', 'i', 'n', ')']).unwrap();
+    let table = thompson(&tree).to_dfa("").to_table();
+    assert!(check_string("i", &table));
+    assert!(check_string("iff", &table));
+    assert!(!check_string("if", &table));
+    assert!(!check_string("in", &table));
+
+    //strings of a and b with both in them, starred
+    let tree = parse(&['(', '(', 'a', '|', 'b', ')', '*', '&', '(', '!', '*', 'a', '!', '*', ')', ')', '*']).unwrap();
+    let table = thompson(&tree).to_dfa("").to_table();
+    assert!(check_string("", &table));
+    assert!(check_string("aba", &table));
+    assert!(!check_string("bb", &table));
+}